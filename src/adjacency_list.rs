@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[derive(Clone, Debug)]
 pub struct GraphEdge {
     pub to: usize,
@@ -9,6 +11,69 @@ pub struct WeightedAdjacencyList {
     pub list: Vec<Vec<GraphEdge>>,
 }
 
+impl WeightedAdjacencyList {
+    pub fn undirected(&self) -> Self {
+        let mut list: Vec<Vec<GraphEdge>> = vec![vec![]; self.list.len()];
+        // where each neighbour already sits in list[a]
+        let mut index: Vec<HashMap<usize, usize>> = vec![HashMap::new(); self.list.len()];
+
+        for (from, edges) in self.list.iter().enumerate() {
+            for edge in edges.iter() {
+                for (a, b) in [(from, edge.to), (edge.to, from)] {
+                    // parallel edges collapse into the cheapest one
+                    match index[a].get(&b) {
+                        Some(&i) => list[a][i].weight = list[a][i].weight.min(edge.weight),
+                        None => {
+                            index[a].insert(b, list[a].len());
+                            list[a].push(GraphEdge {
+                                to: b,
+                                weight: edge.weight,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { list }
+    }
+
+    // like `undirected` but keeps parallel edges: a pair listed k times one
+    // way and j times the other is joined by k.max(j) edges, so a graph that
+    // lists every edge in both directions means the same as one listing it once
+    pub fn undirected_multi(&self) -> Self {
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+        for (from, edges) in self.list.iter().enumerate() {
+            for edge in edges.iter() {
+                *counts.entry((from, edge.to)).or_default() += 1;
+            }
+        }
+
+        let mut list: Vec<Vec<GraphEdge>> = vec![vec![]; self.list.len()];
+        for (from, edges) in self.list.iter().enumerate() {
+            for edge in edges.iter() {
+                let forward = counts[&(from, edge.to)];
+                let backward = counts.get(&(edge.to, from)).copied().unwrap_or(0);
+                // only the direction listed more often adds edges, on ties
+                // the one from the smaller vertex
+                if forward < backward || (forward == backward && from > edge.to) {
+                    continue;
+                }
+
+                list[from].push(edge.clone());
+                if from != edge.to {
+                    list[edge.to].push(GraphEdge {
+                        to: from,
+                        weight: edge.weight,
+                    });
+                }
+            }
+        }
+
+        Self { list }
+    }
+}
+
 fn walk(
     graph: &WeightedAdjacencyList,
     curr: usize,
@@ -54,37 +119,106 @@ pub fn dfs(graph: WeightedAdjacencyList, source: usize, needle: usize) -> Vec<us
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        generators::{gnp, grid, path, random_dag, star, Rng},
+        tests::graph::directed_list,
+    };
 
     #[test]
     fn tets_dfs() {
-        //     >(1)<--->(4) ---->(5)
-        //    /          |       /|
-        // (0)     ------|------- |
-        //    \   v      v        v
-        //     >(2) --> (3) <----(6)
-        let list = WeightedAdjacencyList {
+        let list = directed_list();
+
+        assert_eq!(dfs(list.clone(), 0, 6), vec![0, 1, 4, 5, 6]);
+        assert_eq!(dfs(list, 6, 0), vec![]);
+    }
+
+    #[test]
+    fn test_dfs_generated() {
+        let rng = &mut Rng::new(40);
+        assert!(dfs(path(rng, 50, 9), 0, 49).into_iter().eq(0..50));
+
+        // any walk found through a grid steps between neighbouring cells
+        let grid = grid(rng, 4, 5, 9);
+        let walk = dfs(grid.clone(), 0, 19);
+        assert_eq!((walk[0], walk[walk.len() - 1]), (0, 19));
+        for w in walk.windows(2) {
+            assert!(grid.list[w[0]].iter().any(|edge| edge.to == w[1]));
+        }
+
+        // nothing leads back against the hidden order of a dag
+        let dag = random_dag(rng, 30, 0.2, 9);
+        for (from, edges) in dag.list.iter().enumerate() {
+            for edge in edges {
+                assert_eq!(dfs(dag.clone(), edge.to, from), vec![]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_undirected() {
+        let graph = WeightedAdjacencyList {
             list: vec![
                 vec![
                     GraphEdge { to: 1, weight: 3 },
-                    GraphEdge { to: 2, weight: 1 },
-                ],
-                vec![GraphEdge { to: 4, weight: 1 }],
-                vec![GraphEdge { to: 3, weight: 7 }],
-                vec![],
-                vec![
-                    GraphEdge { to: 1, weight: 1 },
-                    GraphEdge { to: 3, weight: 5 },
-                    GraphEdge { to: 5, weight: 2 },
+                    GraphEdge { to: 1, weight: 2 },
                 ],
                 vec![
-                    GraphEdge { to: 2, weight: 18 },
-                    GraphEdge { to: 6, weight: 1 },
+                    GraphEdge { to: 0, weight: 4 },
+                    GraphEdge { to: 2, weight: 1 },
                 ],
-                vec![GraphEdge { to: 3, weight: 1 }],
+                vec![],
             ],
         };
+        let edges: Vec<Vec<(usize, usize)>> = graph
+            .undirected()
+            .list
+            .iter()
+            .map(|edges| edges.iter().map(|edge| (edge.to, edge.weight)).collect())
+            .collect();
 
-        assert_eq!(dfs(list.clone(), 0, 6), vec![0, 1, 4, 5, 6]);
-        assert_eq!(dfs(list, 6, 0), vec![]);
+        assert_eq!(
+            edges,
+            vec![vec![(1, 2)], vec![(0, 2), (2, 1)], vec![(1, 1)]]
+        );
+
+        let edges: Vec<Vec<(usize, usize)>> = graph
+            .undirected_multi()
+            .list
+            .iter()
+            .map(|edges| edges.iter().map(|edge| (edge.to, edge.weight)).collect())
+            .collect();
+
+        assert_eq!(
+            edges,
+            vec![
+                vec![(1, 3), (1, 2)],
+                vec![(0, 3), (0, 2), (2, 1)],
+                vec![(1, 1)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_undirected_generated() {
+        let rng = &mut Rng::new(40);
+
+        // a hub with every edge listed twice in each direction
+        let mut hub = star(rng, 20_000, 9);
+        for edges in hub.list.iter_mut() {
+            edges.extend(edges.clone());
+        }
+        let undirected = hub.undirected();
+        assert_eq!(undirected.list[0].len(), 19_999);
+        assert!(undirected.list[1..].iter().all(|edges| edges.len() == 1));
+        assert_eq!(hub.undirected_multi().list[0].len(), 2 * 19_999);
+
+        // one direction of a random directed graph is enough for both
+        let graph = gnp(rng, 40, 0.1, true, 9);
+        let undirected = graph.undirected();
+        for (from, edges) in graph.list.iter().enumerate() {
+            for edge in edges {
+                assert!(undirected.list[edge.to].iter().any(|back| back.to == from));
+            }
+        }
     }
 }
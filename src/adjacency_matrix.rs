@@ -1,39 +1,93 @@
-use crate::queue::Queue;
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    queue::Queue,
+};
 
 #[derive(Clone, Debug)]
 pub struct WeightedAdjacencyMatrix {
     pub matrix: Vec<Vec<Option<usize>>>,
 }
 
-pub fn bfs(graph: WeightedAdjacencyMatrix, source: usize, needle: usize) -> Vec<usize> {
-    if needle >= graph.matrix.len() {
-        return vec![];
+impl From<&WeightedAdjacencyList> for WeightedAdjacencyMatrix {
+    fn from(graph: &WeightedAdjacencyList) -> Self {
+        let len = graph.list.len();
+        let mut matrix = vec![vec![None; len]; len];
+
+        for (from, edges) in graph.list.iter().enumerate() {
+            for edge in edges.iter() {
+                // a matrix holds one edge per pair, keep the cheapest
+                let cell: &mut Option<usize> = &mut matrix[from][edge.to];
+                *cell = Some(cell.map_or(edge.weight, |weight| weight.min(edge.weight)));
+            }
+        }
+
+        WeightedAdjacencyMatrix { matrix }
+    }
+}
+
+impl From<&WeightedAdjacencyMatrix> for WeightedAdjacencyList {
+    fn from(graph: &WeightedAdjacencyMatrix) -> Self {
+        WeightedAdjacencyList {
+            list: graph
+                .matrix
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .filter_map(|(to, weight)| weight.map(|weight| GraphEdge { to, weight }))
+                        .collect()
+                })
+                .collect(),
+        }
     }
+}
+
+#[derive(Clone, Debug)]
+pub struct BfsTree {
+    pub prev: Vec<Option<usize>>,
+    pub order: Vec<usize>,
+    pub depth: Vec<Option<usize>>,
+}
 
+fn walk(graph: &WeightedAdjacencyMatrix, source: usize, needle: Option<usize>) -> BfsTree {
     let mut prev = vec![None; graph.matrix.len()];
-    let mut seen = vec![false; graph.matrix.len()];
-    seen[source] = true;
+    let mut depth = vec![None; graph.matrix.len()];
+    let mut order = vec![];
+    depth[source] = Some(0);
     let mut queue = Queue::new();
     queue.enqueue(source);
 
     while queue.lenght() > 0 {
         let curr = queue.deque().unwrap();
+        order.push(curr);
 
-        if curr == needle {
+        if Some(curr) == needle {
             break;
         }
 
         for i in 0..graph.matrix[curr].len() {
-            if let Some(_) = graph.matrix[curr][i] {
-                if !seen[i] {
-                    seen[i] = true;
-                    prev[i] = Some(curr);
-                    queue.enqueue(i);
-                }
+            if graph.matrix[curr][i].is_some() && depth[i].is_none() {
+                depth[i] = depth[curr].map(|d| d + 1);
+                prev[i] = Some(curr);
+                queue.enqueue(i);
             }
         }
     }
 
+    BfsTree { prev, order, depth }
+}
+
+pub fn bfs_tree(graph: &WeightedAdjacencyMatrix, source: usize) -> BfsTree {
+    walk(graph, source, None)
+}
+
+pub fn bfs(graph: WeightedAdjacencyMatrix, source: usize, needle: usize) -> Vec<usize> {
+    if needle >= graph.matrix.len() {
+        return vec![];
+    }
+
+    let prev = walk(&graph, source, Some(needle)).prev;
+
     if matches!(prev[needle], None) {
         return vec![];
     }
@@ -84,4 +138,49 @@ mod tests {
         assert_eq!(bfs(graph.clone(), 0, 6), vec![0, 1, 4, 5, 6]);
         assert_eq!(bfs(graph, 6, 0), vec![])
     }
+
+    #[test]
+    fn test_bfs_tree() {
+        let graph = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(1), Some(2), None, None, None],
+                vec![Some(1), None, None, Some(3), None, None],
+                vec![Some(2), None, None, Some(4), Some(5), None],
+                vec![None, Some(3), Some(4), None, Some(6), Some(7)],
+                vec![None, None, Some(5), Some(6), None, Some(8)],
+                vec![None, None, None, Some(7), Some(8), None],
+            ],
+        };
+        let tree = bfs_tree(&graph, 0);
+        assert_eq!(tree.order, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            tree.prev,
+            vec![None, Some(0), Some(0), Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            tree.depth,
+            vec![Some(0), Some(1), Some(1), Some(2), Some(2), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_list_conversion() {
+        let list = crate::tests::graph::directed_list();
+        let matrix = WeightedAdjacencyMatrix::from(&list);
+        assert_eq!(
+            matrix.matrix[4],
+            vec![None, Some(1), None, Some(5), None, Some(2), None]
+        );
+        assert_eq!(bfs(matrix.clone(), 0, 6), vec![0, 1, 4, 5, 6]);
+
+        let back = WeightedAdjacencyList::from(&matrix);
+        assert_eq!(WeightedAdjacencyMatrix::from(&back).matrix, matrix.matrix);
+
+        let mut parallel = list;
+        parallel.list[0].push(GraphEdge { to: 1, weight: 2 });
+        assert_eq!(
+            WeightedAdjacencyMatrix::from(&parallel).matrix[0][1],
+            Some(2)
+        );
+    }
 }
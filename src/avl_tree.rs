@@ -0,0 +1,289 @@
+use std::cmp::Ordering;
+
+use crate::{
+    binary_search_tree::{keyed_lookups, keyed_tree, Keyed},
+    binary_tree::{BinaryNode, BinaryTree, Link},
+};
+
+#[derive(Clone, Debug)]
+struct AvlEntry<K, V> {
+    key: K,
+    value: V,
+    // longest path down to a leaf, counting nodes
+    height: usize,
+}
+
+impl<K: Ord, V> Keyed for AvlEntry<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+type Node<K, V> = BinaryNode<AvlEntry<K, V>>;
+type AvlLink<K, V> = Link<AvlEntry<K, V>>;
+
+// recursion is fine here: the height stays within 1.44 log2(n)
+#[derive(Clone, Debug)]
+pub struct AvlTree<K, V> {
+    tree: BinaryTree<AvlEntry<K, V>>,
+    len: usize,
+}
+
+fn height<K, V>(link: &AvlLink<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.value.height)
+}
+
+fn balance_factor<K, V>(node: &Node<K, V>) -> isize {
+    height(&node.left) as isize - height(&node.right) as isize
+}
+
+fn update<K, V>(node: &mut Node<K, V>) {
+    node.value.height = 1 + height(&node.left).max(height(&node.right));
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    update(&mut node);
+    left.right = Some(node);
+    update(&mut left);
+    left
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    update(&mut node);
+    right.left = Some(node);
+    update(&mut right);
+    right
+}
+
+fn rebalance<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    update(&mut node);
+
+    match balance_factor(&node) {
+        2 => {
+            // left-right case turns into left-left first
+            if balance_factor(node.left.as_ref().unwrap()) < 0 {
+                node.left = Some(rotate_left(node.left.take().unwrap()));
+            }
+            rotate_right(node)
+        }
+        -2 => {
+            if balance_factor(node.right.as_ref().unwrap()) > 0 {
+                node.right = Some(rotate_right(node.right.take().unwrap()));
+            }
+            rotate_left(node)
+        }
+        _ => node,
+    }
+}
+
+fn insert<K: Ord, V>(link: AvlLink<K, V>, key: K, value: V) -> (Box<Node<K, V>>, Option<V>) {
+    let Some(mut node) = link else {
+        let entry = AvlEntry {
+            key,
+            value,
+            height: 1,
+        };
+        return (BinaryNode::boxed(entry, None, None), None);
+    };
+
+    let old = match key.cmp(&node.value.key) {
+        Ordering::Less => {
+            let (left, old) = insert(node.left.take(), key, value);
+            node.left = Some(left);
+            old
+        }
+        Ordering::Greater => {
+            let (right, old) = insert(node.right.take(), key, value);
+            node.right = Some(right);
+            old
+        }
+        Ordering::Equal => {
+            let old = std::mem::replace(&mut node.value.value, value);
+            return (node, Some(old));
+        }
+    };
+
+    (rebalance(node), old)
+}
+
+// splits off the smallest node, returning it and what is left of the subtree
+fn remove_min<K, V>(mut node: Box<Node<K, V>>) -> (Box<Node<K, V>>, AvlLink<K, V>) {
+    match node.left.take() {
+        None => {
+            let rest = node.right.take();
+            (node, rest)
+        }
+        Some(left) => {
+            let (min, left) = remove_min(left);
+            node.left = left;
+            (min, Some(rebalance(node)))
+        }
+    }
+}
+
+fn remove<K: Ord, V>(link: AvlLink<K, V>, key: &K) -> (AvlLink<K, V>, Option<V>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+
+    let old = match key.cmp(&node.value.key) {
+        Ordering::Less => {
+            let (left, old) = remove(node.left.take(), key);
+            node.left = left;
+            old
+        }
+        Ordering::Greater => {
+            let (right, old) = remove(node.right.take(), key);
+            node.right = right;
+            old
+        }
+        Ordering::Equal => {
+            let node = *node;
+            let replacement = match (node.left, node.right) {
+                (None, right) => right,
+                (left, None) => left,
+                (left, Some(right)) => {
+                    let (mut successor, right) = remove_min(right);
+                    successor.left = left;
+                    successor.right = right;
+                    Some(rebalance(successor))
+                }
+            };
+            return (replacement, Some(node.value.value));
+        }
+    };
+
+    (Some(rebalance(node)), old)
+}
+
+// the height it claims if ordering, cached heights and balance all hold
+fn check<K: Ord, V>(link: &AvlLink<K, V>, low: Option<&K>, high: Option<&K>) -> Option<usize> {
+    let Some(node) = link else {
+        return Some(0);
+    };
+
+    let key = &node.value.key;
+    if low.is_some_and(|low| key <= low) || high.is_some_and(|high| key >= high) {
+        return None;
+    }
+
+    let left = check(&node.left, low, Some(key))?;
+    let right = check(&node.right, Some(key), high)?;
+    let height = 1 + left.max(right);
+    (left.abs_diff(right) <= 1 && node.value.height == height).then_some(height)
+}
+
+impl<K: Ord, V> AvlTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn height(&self) -> usize {
+        height(&self.tree.head)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (head, old) = insert(self.tree.head.take(), key, value);
+        self.tree.head = Some(head);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (head, old) = remove(self.tree.head.take(), key);
+        self.tree.head = head;
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    // keys in order, every cached height right and no balance factor past one
+    pub fn is_valid(&self) -> bool {
+        check(&self.tree.head, None, None).is_some()
+    }
+}
+
+keyed_lookups!(AvlTree);
+keyed_tree!(AvlTree);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::generators::Rng;
+
+    #[test]
+    fn test_rotations() {
+        // each of these orders needs a different rotation at the root
+        for keys in [[1, 2, 3], [3, 2, 1], [1, 3, 2], [3, 1, 2]] {
+            let tree: AvlTree<i32, ()> = keys.into_iter().map(|key| (key, ())).collect();
+            assert!(tree.is_valid());
+            assert_eq!(tree.height(), 2);
+            assert_eq!(tree.tree.head.as_ref().unwrap().value.key, 2);
+        }
+    }
+
+    #[test]
+    fn test_sorted_inserts() {
+        let len = 100_000;
+        let mut tree: AvlTree<usize, usize> = (0..len).map(|key| (key, key)).collect();
+        assert!(tree.is_valid());
+        assert_eq!(tree.len(), len);
+        assert!(tree.height() as f64 <= 1.45 * (len as f64).log2());
+
+        for key in (0..len).step_by(2) {
+            assert_eq!(tree.remove(&key), Some(key));
+        }
+        assert!(tree.is_valid());
+        assert!(tree.iter().map(|(k, _)| *k).eq((1..len).step_by(2)));
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(45);
+        let mut tree = AvlTree::new();
+        let mut map = BTreeMap::new();
+
+        for _ in 0..4000 {
+            let key = rng.below(300);
+            if rng.chance(0.55) {
+                assert_eq!(tree.insert(key, key + 1), map.insert(key, key + 1));
+            } else {
+                assert_eq!(tree.remove(&key), map.remove(&key));
+            }
+
+            assert!(tree.is_valid());
+            assert_eq!(tree.len(), map.len());
+        }
+
+        assert!(tree.iter().eq(map.iter()));
+        assert!(tree.range(50..=120).eq(map.range(50..=120)));
+        assert!((0..300).all(|key| tree.get(&key) == map.get(&key)));
+    }
+}
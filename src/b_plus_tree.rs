@@ -0,0 +1,575 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::page::{self, paged_tree, Error, FixedSize, Page, TreePage};
+
+const MAGIC: &[u8; 4] = b"BPTR";
+const NO_NEXT: u32 = u32::MAX;
+
+#[derive(Clone, Debug)]
+struct Node<K, V, const ORDER: usize> {
+    // entries in a leaf, separators in an inner node
+    keys: Vec<K>,
+    // empty in inner nodes
+    values: Vec<V>,
+    // empty in leaves
+    children: Vec<usize>,
+    // the leaf to the right, if this is a leaf and not the last one
+    next: Option<usize>,
+}
+
+impl<K, V, const ORDER: usize> Node<K, V, ORDER> {
+    fn new() -> Self {
+        Self {
+            keys: vec![],
+            values: vec![],
+            children: vec![],
+            next: None,
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+// a leaf flag, the key count, the next leaf, then room for a full node of
+// keys, values and u32 child ids
+impl<K: FixedSize, V: FixedSize, const ORDER: usize> Page for Node<K, V, ORDER> {
+    const SIZE: usize = 7 + (ORDER - 1) * (K::SIZE + V::SIZE) + ORDER * 4;
+
+    fn encode(&self, page: &mut [u8]) {
+        let values = 7 + (ORDER - 1) * K::SIZE;
+        let children = values + (ORDER - 1) * V::SIZE;
+
+        page[0] = u8::from(self.is_leaf());
+        (self.keys.len() as u16).encode(&mut page[1..]);
+        self.next
+            .map_or(NO_NEXT, |next| next as u32)
+            .encode(&mut page[3..]);
+        page::encode_all(self.keys.iter().copied(), &mut page[7..]);
+        page::encode_all(self.values.iter().copied(), &mut page[values..]);
+        page::encode_all(
+            self.children.iter().map(|&child| child as u32),
+            &mut page[children..],
+        );
+    }
+
+    fn decode(page: &[u8]) -> Result<Self, Error> {
+        let values = 7 + (ORDER - 1) * K::SIZE;
+        let children = values + (ORDER - 1) * V::SIZE;
+
+        let leaf = match page[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidFormat("bad leaf flag")),
+        };
+        let len = u16::decode(&page[1..])? as usize;
+        if len >= ORDER {
+            return Err(Error::InvalidFormat("node holds too many keys"));
+        }
+        let next = u32::decode(&page[3..])?;
+
+        let mut node = Self {
+            keys: page::decode_all(&page[7..], len)?,
+            next: (next != NO_NEXT).then_some(next as usize),
+            ..Self::new()
+        };
+        if leaf {
+            node.values = page::decode_all(&page[values..], len)?;
+        } else {
+            node.children = page::decode_all::<u32>(&page[children..], len + 1)?
+                .into_iter()
+                .map(|child| child as usize)
+                .collect();
+        }
+        Ok(node)
+    }
+}
+
+impl<K: FixedSize, V: FixedSize, const ORDER: usize> TreePage for Node<K, V, ORDER> {
+    fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    fn next(&self) -> Option<usize> {
+        self.next
+    }
+
+    fn renumbered(&self, ids: &[usize]) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: self.values.clone(),
+            children: self.children.iter().map(|&child| ids[child]).collect(),
+            next: self.next.map(|next| ids[next]),
+        }
+    }
+}
+
+enum Insert<K, V> {
+    Done(Option<V>),
+    // the separator for the new right sibling
+    Split(K, usize),
+}
+
+// a B-tree that keeps every entry in its leaves and only copies of keys in
+// the inner nodes; the leaves are chained left to right so a range scan is one
+// descent followed by a walk along the chain
+#[derive(Clone, Debug)]
+pub struct BPlusTree<K, V, const ORDER: usize> {
+    nodes: Vec<Node<K, V, ORDER>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl<K: Ord + Clone, V, const ORDER: usize> BPlusTree<K, V, ORDER> {
+    const MAX_KEYS: usize = ORDER - 1;
+    const MIN_KEYS: usize = (ORDER - 1) / 2;
+
+    pub fn new() -> Self {
+        const {
+            assert!(
+                ORDER >= 3 && ORDER <= u16::MAX as usize,
+                "order out of range"
+            )
+        };
+
+        Self {
+            nodes: vec![Node::new()],
+            free: vec![],
+            root: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut curr = &self.nodes[self.root];
+        while let Some(&child) = curr.children.first() {
+            height += 1;
+            curr = &self.nodes[child];
+        }
+        height
+    }
+
+    // a key equal to a separator lives to its right
+    fn leaf_for(&self, key: &K) -> usize {
+        let mut id = self.root;
+        while !self.nodes[id].is_leaf() {
+            let node = &self.nodes[id];
+            id = node.children[node.keys.partition_point(|k| k <= key)];
+        }
+        id
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let leaf = &self.nodes[self.leaf_for(key)];
+        let i = leaf.keys.binary_search(key).ok()?;
+        Some(&leaf.values[i])
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let id = self.leaf_for(key);
+        let leaf = &mut self.nodes[id];
+        let i = leaf.keys.binary_search(key).ok()?;
+        Some(&mut leaf.values[i])
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn alloc(&mut self, node: Node<K, V, ORDER>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, id: usize) -> Node<K, V, ORDER> {
+        self.free.push(id);
+        std::mem::replace(&mut self.nodes[id], Node::new())
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.insert_into(self.root, key, value) {
+            Insert::Done(old) => {
+                if old.is_none() {
+                    self.len += 1;
+                }
+                old
+            }
+            Insert::Split(key, right) => {
+                let root = Node {
+                    keys: vec![key],
+                    children: vec![self.root, right],
+                    ..Node::new()
+                };
+                self.root = self.alloc(root);
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    fn insert_into(&mut self, id: usize, key: K, value: V) -> Insert<K, V> {
+        let node = &mut self.nodes[id];
+
+        if node.is_leaf() {
+            match node.keys.binary_search(&key) {
+                Ok(i) => return Insert::Done(Some(std::mem::replace(&mut node.values[i], value))),
+                Err(i) => {
+                    node.keys.insert(i, key);
+                    node.values.insert(i, value);
+                }
+            }
+            if node.keys.len() <= Self::MAX_KEYS {
+                return Insert::Done(None);
+            }
+
+            // a leaf splits down the middle and copies the first key on the
+            // right up as the separator
+            let mid = node.keys.len() / 2;
+            let right = Node {
+                keys: node.keys.split_off(mid),
+                values: node.values.split_off(mid),
+                children: vec![],
+                next: node.next,
+            };
+            let separator = right.keys[0].clone();
+            let right = self.alloc(right);
+            self.nodes[id].next = Some(right);
+            return Insert::Split(separator, right);
+        }
+
+        let i = node.keys.partition_point(|k| *k <= key);
+        let child = node.children[i];
+        let (separator, right) = match self.insert_into(child, key, value) {
+            Insert::Split(separator, right) => (separator, right),
+            done => return done,
+        };
+
+        let node = &mut self.nodes[id];
+        node.keys.insert(i, separator);
+        node.children.insert(i + 1, right);
+        if node.keys.len() <= Self::MAX_KEYS {
+            return Insert::Done(None);
+        }
+
+        // an inner node moves its median up instead of copying it
+        let mid = node.keys.len() / 2;
+        let right = Node {
+            keys: node.keys.split_off(mid + 1),
+            children: node.children.split_off(mid + 1),
+            ..Node::new()
+        };
+        let separator = node.keys.pop().unwrap();
+        Insert::Split(separator, self.alloc(right))
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let old = self.remove_from(self.root, key)?;
+        self.len -= 1;
+
+        let root = &self.nodes[self.root];
+        if root.keys.is_empty() && !root.is_leaf() {
+            let child = root.children[0];
+            self.release(self.root);
+            self.root = child;
+        }
+
+        Some(old)
+    }
+
+    // separators of removed keys stay behind, they still split the key space
+    // correctly
+    fn remove_from(&mut self, id: usize, key: &K) -> Option<V> {
+        let node = &mut self.nodes[id];
+        if node.is_leaf() {
+            let i = node.keys.binary_search(key).ok()?;
+            node.keys.remove(i);
+            return Some(node.values.remove(i));
+        }
+
+        let i = node.keys.partition_point(|k| k <= key);
+        let child = node.children[i];
+        let old = self.remove_from(child, key)?;
+        self.fix_child(id, i);
+        Some(old)
+    }
+
+    fn fix_child(&mut self, id: usize, i: usize) {
+        let child = self.nodes[id].children[i];
+        if self.nodes[child].keys.len() >= Self::MIN_KEYS {
+            return;
+        }
+
+        let spare = |sibling: &usize| self.nodes[*sibling].keys.len() > Self::MIN_KEYS;
+        let left = i.checked_sub(1).map(|j| self.nodes[id].children[j]);
+        let right = self.nodes[id].children.get(i + 1).copied();
+        let leaf = self.nodes[child].is_leaf();
+
+        if let Some(left) = left.filter(spare) {
+            let sibling = &mut self.nodes[left];
+            let key = sibling.keys.pop().unwrap();
+
+            if leaf {
+                let value = sibling.values.pop().unwrap();
+                self.nodes[id].keys[i - 1] = key.clone();
+                let node = &mut self.nodes[child];
+                node.keys.insert(0, key);
+                node.values.insert(0, value);
+            } else {
+                let grandchild = sibling.children.pop().unwrap();
+                let key = std::mem::replace(&mut self.nodes[id].keys[i - 1], key);
+                let node = &mut self.nodes[child];
+                node.keys.insert(0, key);
+                node.children.insert(0, grandchild);
+            }
+        } else if let Some(right) = right.filter(spare) {
+            let sibling = &mut self.nodes[right];
+            let key = sibling.keys.remove(0);
+
+            if leaf {
+                let value = sibling.values.remove(0);
+                self.nodes[id].keys[i] = sibling.keys[0].clone();
+                let node = &mut self.nodes[child];
+                node.keys.push(key);
+                node.values.push(value);
+            } else {
+                let grandchild = sibling.children.remove(0);
+                let key = std::mem::replace(&mut self.nodes[id].keys[i], key);
+                let node = &mut self.nodes[child];
+                node.keys.push(key);
+                node.children.push(grandchild);
+            }
+        } else {
+            self.merge(id, if left.is_some() { i - 1 } else { i });
+        }
+    }
+
+    // folds child j + 1 into child j; leaves drop the separator since it was
+    // only ever a copy, inner nodes pull it down between the two halves
+    fn merge(&mut self, id: usize, j: usize) {
+        let parent = &mut self.nodes[id];
+        let separator = parent.keys.remove(j);
+        let left = parent.children[j];
+        let right = parent.children.remove(j + 1);
+        let right = self.release(right);
+
+        let node = &mut self.nodes[left];
+        if node.is_leaf() {
+            node.next = right.next;
+        } else {
+            node.keys.push(separator);
+        }
+        node.keys.extend(right.keys);
+        node.values.extend(right.values);
+        node.children.extend(right.children);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        Range::new(self, ..)
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, &V)> {
+        Range::new(self, range)
+    }
+
+    // leaf depth if keys fall in [low, high) at every level, nodes are within
+    // their size bounds and all leaves sit on the same level
+    fn check(
+        &self,
+        id: usize,
+        low: Option<&K>,
+        high: Option<&K>,
+        leaves: &mut Vec<usize>,
+    ) -> Option<usize> {
+        let node = &self.nodes[id];
+        let len = node.keys.len();
+        let too_small = match id == self.root {
+            true => len == 0 && !node.is_leaf(),
+            false => len < Self::MIN_KEYS,
+        };
+        if too_small || len > Self::MAX_KEYS {
+            return None;
+        }
+
+        if node.keys.windows(2).any(|w| w[0] >= w[1])
+            || low.is_some_and(|low| node.keys[0] < *low)
+            || high.is_some_and(|high| node.keys[len - 1] >= *high)
+        {
+            return None;
+        }
+
+        if node.is_leaf() {
+            leaves.push(id);
+            return (node.values.len() == len).then_some(1);
+        }
+        if node.children.len() != len + 1 || !node.values.is_empty() || node.next.is_some() {
+            return None;
+        }
+
+        let mut depth = None;
+        for (j, &child) in node.children.iter().enumerate() {
+            let low = j.checked_sub(1).map(|j| &node.keys[j]).or(low);
+            let high = node.keys.get(j).or(high);
+            let below = self.check(child, low, high, leaves)?;
+            if depth.is_some_and(|depth| depth != below) {
+                return None;
+            }
+            depth = Some(below);
+        }
+        depth.map(|depth| depth + 1)
+    }
+
+    // also checks that the leaf chain visits every leaf in key order
+    pub fn is_valid(&self) -> bool {
+        let mut leaves = vec![];
+        if self.check(self.root, None, None, &mut leaves).is_none() {
+            return false;
+        }
+
+        let chained = leaves
+            .iter()
+            .zip(leaves.iter().skip(1).map(Some).chain([None]))
+            .all(|(&leaf, next)| self.nodes[leaf].next == next.copied());
+        let len: usize = leaves.iter().map(|&leaf| self.nodes[leaf].keys.len()).sum();
+        chained && len == self.len
+    }
+}
+
+// the leaf chain is checked against the tree itself by `is_valid` on load
+paged_tree!(BPlusTree, MAGIC, "b+ tree", Ord + Clone);
+
+struct Range<'a, K, V, const ORDER: usize, R> {
+    nodes: &'a [Node<K, V, ORDER>],
+    leaf: Option<usize>,
+    index: usize,
+    range: R,
+}
+
+impl<'a, K: Ord + Clone, V, const ORDER: usize, R: RangeBounds<K>> Range<'a, K, V, ORDER, R> {
+    fn new(tree: &'a BPlusTree<K, V, ORDER>, range: R) -> Self {
+        let (leaf, index) = match range.start_bound() {
+            Bound::Included(start) => {
+                let leaf = tree.leaf_for(start);
+                (leaf, tree.nodes[leaf].keys.partition_point(|k| k < start))
+            }
+            Bound::Excluded(start) => {
+                let leaf = tree.leaf_for(start);
+                (leaf, tree.nodes[leaf].keys.partition_point(|k| k <= start))
+            }
+            Bound::Unbounded => {
+                let mut leaf = tree.root;
+                while let Some(&child) = tree.nodes[leaf].children.first() {
+                    leaf = child;
+                }
+                (leaf, 0)
+            }
+        };
+
+        Self {
+            nodes: &tree.nodes,
+            leaf: Some(leaf),
+            index,
+            range,
+        }
+    }
+}
+
+impl<'a, K: Ord, V, const ORDER: usize, R: RangeBounds<K>> Iterator for Range<'a, K, V, ORDER, R> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let nodes = self.nodes;
+        let mut node = &nodes[self.leaf?];
+        while self.index == node.keys.len() {
+            self.leaf = node.next;
+            self.index = 0;
+            node = &nodes[self.leaf?];
+        }
+
+        let key = &node.keys[self.index];
+        let past_end = match self.range.end_bound() {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        };
+        if past_end {
+            self.leaf = None;
+            return None;
+        }
+
+        self.index += 1;
+        Some((key, &node.values[self.index - 1]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    page::paged_tree_tests!(BPlusTree);
+
+    #[test]
+    fn test_leaf_chain() {
+        let len = 20_000;
+        let mut tree: BPlusTree<usize, usize, 16> = (0..len).rev().map(|key| (key, key)).collect();
+        assert!(tree.is_valid());
+        assert!(tree.height() as f64 <= 1.0 + (len as f64).log(8.0));
+
+        for key in (0..len).filter(|key| key % 5 != 0) {
+            tree.remove(&key);
+        }
+        *tree.get_mut(&10).unwrap() = 0;
+        assert!(tree.is_valid());
+
+        // the walk along the leaves alone sees every remaining key in order
+        let mut leaf = Some(tree.leaf_for(&0));
+        let mut keys = vec![];
+        while let Some(id) = leaf {
+            keys.extend(tree.nodes[id].keys.iter().copied());
+            leaf = tree.nodes[id].next;
+        }
+        assert!(keys.into_iter().eq((0..len).step_by(5)));
+        assert_eq!(tree.get(&10), Some(&0));
+    }
+
+    #[test]
+    fn test_load_corrupt() {
+        let path = std::env::temp_dir().join(format!("b_plus_tree_{}.bin", std::process::id()));
+        let mut tree: BPlusTree<u64, (), 6> = (0..1000).map(|key| (key, ())).collect();
+        for key in (0..1000).step_by(4) {
+            tree.remove(&key);
+        }
+
+        tree.save(&path).unwrap();
+        // cut the leaf chain short at the first leaf
+        let mut bytes = std::fs::read(&path).unwrap();
+        let page = Node::<u64, (), 6>::SIZE.max(24);
+        let first_leaf = (1..bytes.len() / page)
+            .find(|id| bytes[id * page] == 1)
+            .unwrap();
+        bytes[first_leaf * page + 3..first_leaf * page + 7].fill(0xff);
+        std::fs::write(&path, &bytes).unwrap();
+        let corrupt = BPlusTree::<u64, (), 6>::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(corrupt, Err(Error::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,571 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::page::{self, paged_tree, Error, FixedSize, Page, TreePage};
+
+const MAGIC: &[u8; 4] = b"BTRE";
+
+#[derive(Clone, Debug)]
+struct Node<K, V, const ORDER: usize> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    // empty for leaves, one more than keys otherwise
+    children: Vec<usize>,
+}
+
+impl<K, V, const ORDER: usize> Node<K, V, ORDER> {
+    fn new() -> Self {
+        Self {
+            keys: vec![],
+            values: vec![],
+            children: vec![],
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+// a leaf flag, the key count, then room for a full node of keys, values and
+// u32 child ids
+impl<K: FixedSize, V: FixedSize, const ORDER: usize> Page for Node<K, V, ORDER> {
+    const SIZE: usize = 3 + (ORDER - 1) * (K::SIZE + V::SIZE) + ORDER * 4;
+
+    fn encode(&self, page: &mut [u8]) {
+        let values = 3 + (ORDER - 1) * K::SIZE;
+        let children = values + (ORDER - 1) * V::SIZE;
+
+        page[0] = u8::from(self.is_leaf());
+        (self.keys.len() as u16).encode(&mut page[1..]);
+        page::encode_all(self.keys.iter().copied(), &mut page[3..]);
+        page::encode_all(self.values.iter().copied(), &mut page[values..]);
+        page::encode_all(
+            self.children.iter().map(|&child| child as u32),
+            &mut page[children..],
+        );
+    }
+
+    fn decode(page: &[u8]) -> Result<Self, Error> {
+        let values = 3 + (ORDER - 1) * K::SIZE;
+        let children = values + (ORDER - 1) * V::SIZE;
+
+        let leaf = match page[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidFormat("bad leaf flag")),
+        };
+        let len = u16::decode(&page[1..])? as usize;
+        if len >= ORDER {
+            return Err(Error::InvalidFormat("node holds too many keys"));
+        }
+
+        Ok(Self {
+            keys: page::decode_all(&page[3..], len)?,
+            values: page::decode_all(&page[values..], len)?,
+            children: match leaf {
+                true => vec![],
+                false => page::decode_all::<u32>(&page[children..], len + 1)?
+                    .into_iter()
+                    .map(|child| child as usize)
+                    .collect(),
+            },
+        })
+    }
+}
+
+impl<K: FixedSize, V: FixedSize, const ORDER: usize> TreePage for Node<K, V, ORDER> {
+    fn children(&self) -> &[usize] {
+        &self.children
+    }
+
+    fn renumbered(&self, ids: &[usize]) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: self.values.clone(),
+            children: self.children.iter().map(|&child| ids[child]).collect(),
+        }
+    }
+}
+
+enum Insert<K, V> {
+    Done(Option<V>),
+    // the median that moved up and the new right sibling
+    Split(K, V, usize),
+}
+
+// every node has at most ORDER children and all but the root at least half
+// that many, so the tree stays log_{ORDER/2}(n) deep. nodes live in an arena
+// and point at each other by index, which maps straight onto page ids
+#[derive(Clone, Debug)]
+pub struct BTree<K, V, const ORDER: usize> {
+    nodes: Vec<Node<K, V, ORDER>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl<K: Ord, V, const ORDER: usize> BTree<K, V, ORDER> {
+    const MAX_KEYS: usize = ORDER - 1;
+    const MIN_KEYS: usize = (ORDER - 1) / 2;
+
+    pub fn new() -> Self {
+        const {
+            assert!(
+                ORDER >= 3 && ORDER <= u16::MAX as usize,
+                "order out of range"
+            )
+        };
+
+        Self {
+            nodes: vec![Node::new()],
+            free: vec![],
+            root: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut curr = &self.nodes[self.root];
+        while let Some(&child) = curr.children.first() {
+            height += 1;
+            curr = &self.nodes[child];
+        }
+        height
+    }
+
+    fn find(&self, key: &K) -> Option<(usize, usize)> {
+        let mut id = self.root;
+        loop {
+            let node = &self.nodes[id];
+            match node.keys.binary_search(key) {
+                Ok(i) => return Some((id, i)),
+                Err(_) if node.is_leaf() => return None,
+                Err(i) => id = node.children[i],
+            }
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|(id, i)| &self.nodes[id].values[i])
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find(key).map(|(id, i)| &mut self.nodes[id].values[i])
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut curr = &self.nodes[self.root];
+        while let Some(&child) = curr.children.last() {
+            curr = &self.nodes[child];
+        }
+        curr.keys.last().zip(curr.values.last())
+    }
+
+    fn alloc(&mut self, node: Node<K, V, ORDER>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, id: usize) -> Node<K, V, ORDER> {
+        self.free.push(id);
+        std::mem::replace(&mut self.nodes[id], Node::new())
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.insert_into(self.root, key, value) {
+            Insert::Done(old) => {
+                if old.is_none() {
+                    self.len += 1;
+                }
+                old
+            }
+            Insert::Split(key, value, right) => {
+                // the only place the tree grows taller
+                let root = Node {
+                    keys: vec![key],
+                    values: vec![value],
+                    children: vec![self.root, right],
+                };
+                self.root = self.alloc(root);
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    fn insert_into(&mut self, id: usize, key: K, value: V) -> Insert<K, V> {
+        let node = &mut self.nodes[id];
+        let i = match node.keys.binary_search(&key) {
+            Ok(i) => return Insert::Done(Some(std::mem::replace(&mut node.values[i], value))),
+            Err(i) => i,
+        };
+
+        if node.is_leaf() {
+            node.keys.insert(i, key);
+            node.values.insert(i, value);
+        } else {
+            let child = node.children[i];
+            let (key, value, right) = match self.insert_into(child, key, value) {
+                Insert::Split(key, value, right) => (key, value, right),
+                done => return done,
+            };
+            let node = &mut self.nodes[id];
+            node.keys.insert(i, key);
+            node.values.insert(i, value);
+            node.children.insert(i + 1, right);
+        }
+
+        let node = &mut self.nodes[id];
+        if node.keys.len() <= Self::MAX_KEYS {
+            return Insert::Done(None);
+        }
+
+        // an overfull node keeps the lower half and hands its median up
+        let mid = node.keys.len() / 2;
+        let mut right = Node::new();
+        right.keys = node.keys.split_off(mid + 1);
+        right.values = node.values.split_off(mid + 1);
+        if !node.is_leaf() {
+            right.children = node.children.split_off(mid + 1);
+        }
+        let key = node.keys.pop().unwrap();
+        let value = node.values.pop().unwrap();
+
+        Insert::Split(key, value, self.alloc(right))
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let old = self.remove_from(self.root, key)?;
+        self.len -= 1;
+
+        // the only place the tree gets shorter
+        let root = &self.nodes[self.root];
+        if root.keys.is_empty() && !root.is_leaf() {
+            let child = root.children[0];
+            self.release(self.root);
+            self.root = child;
+        }
+
+        Some(old)
+    }
+
+    fn remove_from(&mut self, id: usize, key: &K) -> Option<V> {
+        let node = &mut self.nodes[id];
+        match node.keys.binary_search(key) {
+            Ok(i) if node.is_leaf() => {
+                node.keys.remove(i);
+                Some(node.values.remove(i))
+            }
+            Ok(i) => {
+                // the predecessor comes up from a leaf and that leaf shrinks instead
+                let child = node.children[i];
+                let (key, value) = self.pop_last(child);
+                let node = &mut self.nodes[id];
+                node.keys[i] = key;
+                let old = std::mem::replace(&mut node.values[i], value);
+                self.fix_child(id, i);
+                Some(old)
+            }
+            Err(_) if node.is_leaf() => None,
+            Err(i) => {
+                let child = node.children[i];
+                let old = self.remove_from(child, key)?;
+                self.fix_child(id, i);
+                Some(old)
+            }
+        }
+    }
+
+    fn pop_last(&mut self, id: usize) -> (K, V) {
+        let node = &mut self.nodes[id];
+        if node.is_leaf() {
+            return (node.keys.pop().unwrap(), node.values.pop().unwrap());
+        }
+
+        let i = node.children.len() - 1;
+        let child = node.children[i];
+        let out = self.pop_last(child);
+        self.fix_child(id, i);
+        out
+    }
+
+    // tops up a child that fell below the minimum, borrowing through the
+    // parent from a sibling with keys to spare or else merging with one
+    fn fix_child(&mut self, id: usize, i: usize) {
+        let child = self.nodes[id].children[i];
+        if self.nodes[child].keys.len() >= Self::MIN_KEYS {
+            return;
+        }
+
+        let spare = |sibling: &usize| self.nodes[*sibling].keys.len() > Self::MIN_KEYS;
+        let left = i.checked_sub(1).map(|j| self.nodes[id].children[j]);
+        let right = self.nodes[id].children.get(i + 1).copied();
+
+        if let Some(left) = left.filter(spare) {
+            let sibling = &mut self.nodes[left];
+            let key = sibling.keys.pop().unwrap();
+            let value = sibling.values.pop().unwrap();
+            let grandchild = sibling.children.pop();
+
+            let parent = &mut self.nodes[id];
+            let key = std::mem::replace(&mut parent.keys[i - 1], key);
+            let value = std::mem::replace(&mut parent.values[i - 1], value);
+
+            let node = &mut self.nodes[child];
+            node.keys.insert(0, key);
+            node.values.insert(0, value);
+            if let Some(grandchild) = grandchild {
+                node.children.insert(0, grandchild);
+            }
+        } else if let Some(right) = right.filter(spare) {
+            let sibling = &mut self.nodes[right];
+            let key = sibling.keys.remove(0);
+            let value = sibling.values.remove(0);
+            let grandchild = (!sibling.is_leaf()).then(|| sibling.children.remove(0));
+
+            let parent = &mut self.nodes[id];
+            let key = std::mem::replace(&mut parent.keys[i], key);
+            let value = std::mem::replace(&mut parent.values[i], value);
+
+            let node = &mut self.nodes[child];
+            node.keys.push(key);
+            node.values.push(value);
+            node.children.extend(grandchild);
+        } else {
+            self.merge(id, if left.is_some() { i - 1 } else { i });
+        }
+    }
+
+    // folds children j + 1 and the key between them into child j
+    fn merge(&mut self, id: usize, j: usize) {
+        let parent = &mut self.nodes[id];
+        let key = parent.keys.remove(j);
+        let value = parent.values.remove(j);
+        let left = parent.children[j];
+        let right = parent.children.remove(j + 1);
+        let right = self.release(right);
+
+        let node = &mut self.nodes[left];
+        node.keys.push(key);
+        node.keys.extend(right.keys);
+        node.values.push(value);
+        node.values.extend(right.values);
+        node.children.extend(right.children);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        Range::new(self, ..)
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, &V)> {
+        Range::new(self, range)
+    }
+
+    // leaf depth if the keys are in order, every node is within its size
+    // bounds and all leaves sit on the same level
+    fn check(&self, id: usize, low: Option<&K>, high: Option<&K>) -> Option<usize> {
+        let node = &self.nodes[id];
+        let len = node.keys.len();
+        let too_small = match id == self.root {
+            true => len == 0 && !node.is_leaf(),
+            false => len < Self::MIN_KEYS,
+        };
+        if too_small || len > Self::MAX_KEYS || node.values.len() != len {
+            return None;
+        }
+
+        if node.keys.windows(2).any(|w| w[0] >= w[1])
+            || low.is_some_and(|low| node.keys[0] <= *low)
+            || high.is_some_and(|high| node.keys[len - 1] >= *high)
+        {
+            return None;
+        }
+
+        if node.is_leaf() {
+            return Some(1);
+        }
+        if node.children.len() != len + 1 {
+            return None;
+        }
+
+        let mut depth = None;
+        for (j, &child) in node.children.iter().enumerate() {
+            let low = j.checked_sub(1).map(|j| &node.keys[j]).or(low);
+            let high = node.keys.get(j).or(high);
+            let below = self.check(child, low, high)?;
+            if depth.is_some_and(|depth| depth != below) {
+                return None;
+            }
+            depth = Some(below);
+        }
+        depth.map(|depth| depth + 1)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.check(self.root, None, None).is_some() && self.iter().count() == self.len
+    }
+}
+
+paged_tree!(BTree, MAGIC, "b-tree", Ord);
+
+struct Range<'a, K, V, const ORDER: usize, R> {
+    nodes: &'a [Node<K, V, ORDER>],
+    // (node, next key to yield) for the path from the root, the subtree
+    // before that key having been visited already
+    stack: Vec<(usize, usize)>,
+    range: R,
+}
+
+impl<'a, K: Ord, V, const ORDER: usize, R: RangeBounds<K>> Range<'a, K, V, ORDER, R> {
+    fn new(tree: &'a BTree<K, V, ORDER>, range: R) -> Self {
+        let mut stack = vec![];
+        let mut id = tree.root;
+
+        loop {
+            let node = &tree.nodes[id];
+            let (i, hit) = match range.start_bound() {
+                Bound::Included(start) => {
+                    let i = node.keys.partition_point(|key| key < start);
+                    (i, node.keys.get(i) == Some(start))
+                }
+                Bound::Excluded(start) => (node.keys.partition_point(|key| key <= start), false),
+                Bound::Unbounded => (0, false),
+            };
+            stack.push((id, i));
+
+            // everything below an exact hit is smaller than the start
+            if node.is_leaf() || hit {
+                break;
+            }
+            id = node.children[i];
+        }
+
+        Self {
+            nodes: &tree.nodes,
+            stack,
+            range,
+        }
+    }
+}
+
+impl<'a, K: Ord, V, const ORDER: usize, R: RangeBounds<K>> Iterator for Range<'a, K, V, ORDER, R> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let nodes = self.nodes;
+        let (id, i) = loop {
+            let &(id, i) = self.stack.last()?;
+            if i < nodes[id].keys.len() {
+                break (id, i);
+            }
+            self.stack.pop();
+        };
+        self.stack.last_mut().unwrap().1 += 1;
+
+        let node = &nodes[id];
+        if !node.is_leaf() {
+            let mut child = node.children[i + 1];
+            loop {
+                self.stack.push((child, 0));
+                match nodes[child].children.first() {
+                    Some(&first) => child = first,
+                    None => break,
+                }
+            }
+        }
+
+        let key = &node.keys[i];
+        let past_end = match self.range.end_bound() {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        };
+        if past_end {
+            self.stack.clear();
+            return None;
+        }
+
+        Some((key, &node.values[i]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    page::paged_tree_tests!(BTree);
+
+    #[test]
+    fn test_sorted_inserts() {
+        let len = 20_000;
+        let mut tree: BTree<usize, usize, 8> = (0..len).map(|key| (key, key)).collect();
+        assert!(tree.is_valid());
+        assert!(tree.height() as f64 <= 1.0 + (len as f64).log(4.0));
+
+        *tree.get_mut(&8).unwrap() = 0;
+        assert_eq!(tree.get(&8), Some(&0));
+        assert_eq!(tree.first_key_value(), Some((&0, &0)));
+        assert_eq!(tree.last_key_value(), Some((&(len - 1), &(len - 1))));
+
+        for key in (0..len).rev().step_by(2) {
+            assert_eq!(tree.remove(&key), Some(key));
+        }
+        assert!(tree.is_valid());
+        assert!(tree.iter().map(|(key, _)| *key).eq((0..len).step_by(2)));
+
+        // freed nodes get reused rather than growing the arena
+        let nodes = tree.nodes.len();
+        for key in (len..2 * len).step_by(4) {
+            tree.insert(key, key);
+        }
+        assert!(tree.nodes.len() <= nodes);
+    }
+
+    #[test]
+    fn test_load_corrupt() {
+        let path = std::env::temp_dir().join(format!("b_tree_{}.bin", std::process::id()));
+        let mut tree: BTree<u32, i64, 5> = (0..1000).map(|key| (key, -(key as i64))).collect();
+        for key in (0..1000).step_by(3) {
+            tree.remove(&key);
+        }
+
+        tree.save(&path).unwrap();
+        let other_type = crate::b_plus_tree::BPlusTree::<u32, i64, 5>::load(&path);
+
+        // the first key of the root becomes larger than everything after it
+        let mut bytes = std::fs::read(&path).unwrap();
+        let page = Node::<u32, i64, 5>::SIZE;
+        bytes[page + 3..page + 7].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+        let corrupt = BTree::<u32, i64, 5>::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(other_type, Err(Error::InvalidFormat(_))));
+        assert!(matches!(corrupt, Err(Error::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,537 @@
+use std::{
+    cmp::Ordering,
+    ops::{Bound, RangeBounds},
+};
+
+use crate::{
+    binary_tree::{BinaryNode, BinaryTree, Link},
+    stack::Stack,
+};
+
+#[derive(Clone, Debug)]
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    // nodes in the subtree rooted here, for rank and select
+    size: usize,
+}
+
+type Node<K, V> = BinaryNode<Entry<K, V>>;
+
+// walks are iterative throughout: nothing keeps this tree balanced, so sorted
+// inserts leave it as deep as it is long
+#[derive(Clone, Debug)]
+pub struct BinarySearchTree<K, V> {
+    tree: BinaryTree<Entry<K, V>>,
+}
+
+fn size<K, V>(link: &Link<Entry<K, V>>) -> usize {
+    link.as_ref().map_or(0, |node| node.value.size)
+}
+
+// unlinks the smallest node under a non empty link
+fn pop_min<K, V>(mut link: &mut Link<Entry<K, V>>) -> Box<Node<K, V>> {
+    while link.as_ref().unwrap().left.is_some() {
+        let node = link.as_mut().unwrap();
+        node.value.size -= 1;
+        link = &mut node.left;
+    }
+
+    let mut min = link.take().unwrap();
+    *link = min.right.take();
+    min
+}
+
+impl<K: Ord, V> BinarySearchTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.tree.head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.head.is_none()
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut curr = self.tree.head.as_deref_mut();
+        while let Some(node) = curr {
+            curr = match key.cmp(&node.value.key) {
+                Ordering::Less => node.left.as_deref_mut(),
+                Ordering::Greater => node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value.value),
+            };
+        }
+        None
+    }
+
+    // returns the previous value when the key was already there
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        let mut link = &mut self.tree.head;
+        while let Some(node) = link {
+            node.value.size += 1;
+            link = if key < node.value.key {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+
+        *link = Some(BinaryNode::boxed(
+            Entry {
+                key,
+                value,
+                size: 1,
+            },
+            None,
+            None,
+        ));
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        find(&self.tree.head, key)?;
+
+        let mut link = &mut self.tree.head;
+        while link.as_ref().unwrap().value.key != *key {
+            let node = link.as_mut().unwrap();
+            node.value.size -= 1;
+            link = if *key < node.value.key {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+
+        let mut node = link.take().unwrap();
+        *link = match (node.left.take(), node.right.take()) {
+            // a leaf or a single child simply moves up
+            (None, right) => right,
+            (left, None) => left,
+            // two children: the successor takes the removed node's place
+            (left, mut right) => {
+                let mut successor = pop_min(&mut right);
+                successor.value.size = node.value.size - 1;
+                successor.left = left;
+                successor.right = right;
+                Some(successor)
+            }
+        };
+
+        Some(node.value.value)
+    }
+
+    pub fn min(&self) -> Option<(&K, &V)> {
+        let mut curr = self.tree.head.as_deref()?;
+        while let Some(left) = curr.left.as_deref() {
+            curr = left;
+        }
+        Some((&curr.value.key, &curr.value.value))
+    }
+
+    pub fn max(&self) -> Option<(&K, &V)> {
+        let mut curr = self.tree.head.as_deref()?;
+        while let Some(right) = curr.right.as_deref() {
+            curr = right;
+        }
+        Some((&curr.value.key, &curr.value.value))
+    }
+
+    // largest key not above `key`
+    pub fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        let mut best = None;
+        let mut curr = self.tree.head.as_deref();
+        while let Some(node) = curr {
+            match key.cmp(&node.value.key) {
+                Ordering::Less => curr = node.left.as_deref(),
+                Ordering::Equal => return Some((&node.value.key, &node.value.value)),
+                Ordering::Greater => {
+                    best = Some((&node.value.key, &node.value.value));
+                    curr = node.right.as_deref();
+                }
+            }
+        }
+        best
+    }
+
+    // smallest key not below `key`
+    pub fn ceiling(&self, key: &K) -> Option<(&K, &V)> {
+        let mut best = None;
+        let mut curr = self.tree.head.as_deref();
+        while let Some(node) = curr {
+            match key.cmp(&node.value.key) {
+                Ordering::Greater => curr = node.right.as_deref(),
+                Ordering::Equal => return Some((&node.value.key, &node.value.value)),
+                Ordering::Less => {
+                    best = Some((&node.value.key, &node.value.value));
+                    curr = node.left.as_deref();
+                }
+            }
+        }
+        best
+    }
+
+    // how many keys are smaller than `key`
+    pub fn rank(&self, key: &K) -> usize {
+        let mut rank = 0;
+        let mut curr = self.tree.head.as_deref();
+        while let Some(node) = curr {
+            match key.cmp(&node.value.key) {
+                Ordering::Less => curr = node.left.as_deref(),
+                Ordering::Equal => return rank + size(&node.left),
+                Ordering::Greater => {
+                    rank += size(&node.left) + 1;
+                    curr = node.right.as_deref();
+                }
+            }
+        }
+        rank
+    }
+
+    // the entry with `index` smaller keys, counting from zero
+    pub fn select(&self, mut index: usize) -> Option<(&K, &V)> {
+        let mut curr = self.tree.head.as_deref();
+        while let Some(node) = curr {
+            let left = size(&node.left);
+            match index.cmp(&left) {
+                Ordering::Less => curr = node.left.as_deref(),
+                Ordering::Equal => return Some((&node.value.key, &node.value.value)),
+                Ordering::Greater => {
+                    index -= left + 1;
+                    curr = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    // ordering of keys and the cached subtree sizes both hold
+    pub fn is_valid(&self) -> bool {
+        let mut stack = Stack::new();
+        if let Some(head) = self.tree.head.as_deref() {
+            stack.push(head);
+        }
+
+        while let Some(node) = stack.pop() {
+            if node.value.size != 1 + size(&node.left) + size(&node.right) {
+                return false;
+            }
+            for child in [node.left.as_deref(), node.right.as_deref()]
+                .into_iter()
+                .flatten()
+            {
+                stack.push(child);
+            }
+        }
+
+        let mut keys = self.iter().map(|(key, _)| key);
+        let mut prev = keys.next();
+        keys.all(|key| {
+            let ordered = prev.is_none_or(|prev| prev < key);
+            prev = Some(key);
+            ordered
+        })
+    }
+}
+
+// what the ordered trees of this crate keep in their nodes, so they can share
+// the lookups, the range walk and the impls below
+pub(crate) trait Keyed {
+    type Key: Ord;
+    type Value;
+
+    fn key(&self) -> &Self::Key;
+    fn value(&self) -> &Self::Value;
+}
+
+impl<K: Ord, V> Keyed for Entry<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+pub(crate) fn find<'a, E: Keyed>(head: &'a Link<E>, key: &E::Key) -> Option<&'a BinaryNode<E>> {
+    let mut curr = head.as_deref();
+    while let Some(node) = curr {
+        curr = match key.cmp(node.value.key()) {
+            Ordering::Less => node.left.as_deref(),
+            Ordering::Greater => node.right.as_deref(),
+            Ordering::Equal => return Some(node),
+        };
+    }
+    None
+}
+
+// get and contains_key for a tree kept in a `tree: BinaryTree<impl Keyed>`
+// field; splay trees reshape on lookups and write their own
+macro_rules! keyed_lookups {
+    ($tree:ident) => {
+        impl<K: Ord, V> $tree<K, V> {
+            pub fn get(&self, key: &K) -> Option<&V> {
+                $crate::binary_search_tree::find(&self.tree.head, key)
+                    .map(|node| $crate::binary_search_tree::Keyed::value(&node.value))
+            }
+
+            pub fn contains_key(&self, key: &K) -> bool {
+                self.get(key).is_some()
+            }
+        }
+    };
+}
+
+// iteration plus Default and FromIterator, which need `new` and `insert`
+macro_rules! keyed_tree {
+    ($tree:ident) => {
+        impl<K: Ord, V> $tree<K, V> {
+            pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+                use $crate::binary_search_tree::Keyed;
+                self.tree
+                    .in_order()
+                    .map(|entry| (entry.key(), entry.value()))
+            }
+
+            pub fn range<R: ::std::ops::RangeBounds<K>>(
+                &self,
+                range: R,
+            ) -> impl Iterator<Item = (&K, &V)> {
+                $crate::binary_search_tree::Range::new(&self.tree.head, range)
+            }
+        }
+
+        impl<K: Ord, V> Default for $tree<K, V> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<K: Ord, V> FromIterator<(K, V)> for $tree<K, V> {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+                let mut tree = Self::new();
+                for (key, value) in iter {
+                    tree.insert(key, value);
+                }
+                tree
+            }
+        }
+    };
+}
+
+pub(crate) use {keyed_lookups, keyed_tree};
+
+keyed_lookups!(BinarySearchTree);
+keyed_tree!(BinarySearchTree);
+
+pub(crate) struct Range<'a, E, R> {
+    stack: Stack<&'a BinaryNode<E>>,
+    curr: Option<&'a BinaryNode<E>>,
+    range: R,
+}
+
+impl<'a, E> Range<'a, E, ()> {
+    pub(crate) fn new<R>(head: &'a Link<E>, range: R) -> Range<'a, E, R> {
+        Range {
+            stack: Stack::new(),
+            curr: head.as_deref(),
+            range,
+        }
+    }
+}
+
+impl<'a, E: Keyed, R: RangeBounds<E::Key>> Iterator for Range<'a, E, R> {
+    type Item = (&'a E::Key, &'a E::Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // an in order walk that never steps into subtrees below the start
+        while let Some(node) = self.curr {
+            let key = node.value.key();
+            let below = match self.range.start_bound() {
+                Bound::Included(start) => key < start,
+                Bound::Excluded(start) => key <= start,
+                Bound::Unbounded => false,
+            };
+
+            if below {
+                self.curr = node.right.as_deref();
+            } else {
+                self.stack.push(node);
+                self.curr = node.left.as_deref();
+            }
+        }
+
+        let node = self.stack.pop()?;
+        let key = node.value.key();
+        let beyond = match self.range.end_bound() {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        };
+        if beyond {
+            self.stack = Stack::new();
+            return None;
+        }
+
+        self.curr = node.right.as_deref();
+        Some((key, node.value.value()))
+    }
+}
+
+// in order values strictly increase, so any BinaryTree can be checked
+pub fn is_valid_bst<T: PartialOrd>(tree: &BinaryTree<T>) -> bool {
+    let mut values = tree.in_order();
+    let mut prev = values.next();
+    values.all(|value| {
+        let ordered = prev.is_none_or(|prev| prev < value);
+        prev = Some(value);
+        ordered
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::generators::Rng;
+
+    fn sample() -> BinarySearchTree<isize, &'static str> {
+        //          20
+        //        /    \
+        //      10      50
+        //     /  \    /  \
+        //    5   15  30  100
+        //              \
+        //              45
+        [20, 10, 50, 5, 15, 30, 100, 45]
+            .into_iter()
+            .map(|key| (key, "v"))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_valid_bst() {
+        assert!(is_valid_bst(&crate::tests::tree::sorted_tree()));
+        assert!(!is_valid_bst(&crate::tests::tree::tree()));
+        assert!(is_valid_bst(&BinaryTree::<isize>::new()));
+        assert!(sample().is_valid());
+    }
+
+    #[test]
+    fn test_queries() {
+        let tree = sample();
+        assert_eq!(tree.len(), 8);
+        assert_eq!(tree.get(&45), Some(&"v"));
+        assert_eq!(tree.get(&46), None);
+        assert_eq!(tree.min(), Some((&5, &"v")));
+        assert_eq!(tree.max(), Some((&100, &"v")));
+
+        assert_eq!(tree.floor(&29).map(|(k, _)| *k), Some(20));
+        assert_eq!(tree.floor(&30).map(|(k, _)| *k), Some(30));
+        assert_eq!(tree.floor(&4), None);
+        assert_eq!(tree.ceiling(&31).map(|(k, _)| *k), Some(45));
+        assert_eq!(tree.ceiling(&101), None);
+
+        assert_eq!(tree.rank(&5), 0);
+        assert_eq!(tree.rank(&30), 4);
+        assert_eq!(tree.rank(&31), 5);
+        assert_eq!(tree.rank(&1000), 8);
+        assert_eq!(tree.select(4).map(|(k, _)| *k), Some(30));
+        assert_eq!(tree.select(8), None);
+
+        fn keys<'a>(range: impl Iterator<Item = (&'a isize, &'a &'a str)>) -> Vec<isize> {
+            range.map(|(k, _)| *k).collect()
+        }
+        assert_eq!(keys(tree.range(10..45)), vec![10, 15, 20, 30]);
+        assert_eq!(keys(tree.range(11..=45)), vec![15, 20, 30, 45]);
+        assert_eq!(keys(tree.range(..15)), vec![5, 10]);
+        assert_eq!(keys(tree.range(46..)), vec![50, 100]);
+        assert_eq!(keys(tree.range(31..44)), vec![]);
+    }
+
+    #[test]
+    fn test_remove() {
+        // a leaf, a node with one child and one with two
+        for (key, remaining) in [
+            (45, vec![5, 10, 15, 20, 30, 50, 100]),
+            (30, vec![5, 10, 15, 20, 45, 50, 100]),
+            (20, vec![5, 10, 15, 30, 45, 50, 100]),
+        ] {
+            let mut tree = sample();
+            assert_eq!(tree.remove(&key), Some("v"));
+            assert_eq!(tree.remove(&key), None);
+            assert!(tree.is_valid());
+            assert_eq!(tree.iter().map(|(k, _)| *k).collect::<Vec<_>>(), remaining);
+        }
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(44);
+        let mut tree = BinarySearchTree::new();
+        let mut map = BTreeMap::new();
+
+        for _ in 0..3000 {
+            let key = rng.below(500);
+            if rng.chance(0.6) {
+                assert_eq!(tree.insert(key, key * 2), map.insert(key, key * 2));
+            } else {
+                assert_eq!(tree.remove(&key), map.remove(&key));
+            }
+        }
+
+        assert!(tree.is_valid());
+        assert_eq!(tree.len(), map.len());
+        assert!(tree.iter().eq(map.iter()));
+        assert!(tree.range(100..200).eq(map.range(100..200)));
+        for key in 0..500 {
+            assert_eq!(tree.floor(&key), map.range(..=key).next_back());
+            assert_eq!(tree.ceiling(&key), map.range(key..).next());
+            assert_eq!(tree.rank(&key), map.range(..key).count());
+        }
+    }
+
+    #[test]
+    fn test_sorted_inserts() {
+        let len = 10_000;
+        let mut tree: BinarySearchTree<usize, ()> = (0..len).map(|key| (key, ())).collect();
+        assert_eq!(tree.select(len - 1), Some((&(len - 1), &())));
+        assert_eq!(tree.remove(&0), Some(()));
+        assert_eq!(tree.min(), Some((&1, &())));
+    }
+
+    #[test]
+    fn test_clone_deep_tree() {
+        // the shape sorted inserts leave, put together directly since the
+        // inserts themselves take quadratic time
+        let len = 300_000;
+        let mut tree = BinarySearchTree::new();
+        for key in 0..len {
+            let entry = Entry {
+                key,
+                value: (),
+                size: key + 1,
+            };
+            tree.tree.head = Some(BinaryNode::boxed(entry, tree.tree.head.take(), None));
+        }
+
+        let copy = tree.clone();
+        assert!(copy.is_valid());
+        assert!(copy.iter().eq(tree.iter()));
+        assert_eq!(copy.select(len - 1), Some((&(len - 1), &())));
+        assert!(format!("{:?}", copy).len() > len);
+    }
+}
@@ -1,17 +1,19 @@
-use crate::queue::Queue;
+use std::fmt::{self, Debug};
 
-type Link<T> = Option<Box<BinaryNode<T>>>; // alias
+use crate::{queue::Queue, stack::Stack, tree_queries::fold};
 
-#[derive(Clone, Debug)]
+pub(crate) type Link<T> = Option<Box<BinaryNode<T>>>; // alias
+
+// Clone, Debug and PartialEq are written out below: derived ones recurse
+// once per level, which a long spine overflows
 pub struct BinaryTree<T> {
-    head: Link<T>,
+    pub(crate) head: Link<T>,
 }
 
-#[derive(Clone, Debug)]
 pub struct BinaryNode<T> {
-    value: T,
-    left: Link<T>,
-    right: Link<T>,
+    pub(crate) value: T,
+    pub(crate) left: Link<T>,
+    pub(crate) right: Link<T>,
 }
 
 impl<T> BinaryTree<T> {
@@ -37,22 +39,75 @@ pub fn compare<T: PartialEq>(
     a: &Option<Box<BinaryNode<T>>>,
     b: &Option<Box<BinaryNode<T>>>,
 ) -> bool {
-    if a.is_none() && b.is_none() {
-        return true;
+    let mut stack = Stack::new();
+    stack.push((a, b));
+
+    while let Some((a, b)) = stack.pop() {
+        match (a, b) {
+            (None, None) => {}
+            (Some(a), Some(b)) if a.value == b.value => {
+                stack.push((&a.right, &b.right));
+                stack.push((&a.left, &b.left));
+            }
+            _ => return false,
+        }
     }
 
-    if a.is_none() || b.is_none() {
-        return false;
+    true
+}
+
+fn clone_link<T: Clone>(link: &Link<T>) -> Link<T> {
+    fold(link, |node, left, right| {
+        BinaryNode::boxed(node.value.clone(), left, right)
+    })
+}
+
+impl<T: Clone> Clone for BinaryTree<T> {
+    fn clone(&self) -> Self {
+        Self {
+            head: clone_link(&self.head),
+        }
     }
+}
 
-    let a = a.as_ref().unwrap();
-    let b = b.as_ref().unwrap();
+impl<T: Clone> Clone for BinaryNode<T> {
+    fn clone(&self) -> Self {
+        Self::new(
+            self.value.clone(),
+            clone_link(&self.left),
+            clone_link(&self.right),
+        )
+    }
+}
 
-    if a.value != b.value {
-        return false;
+// values in pre-order with None for every missing child, which is enough to
+// tell two shapes apart
+fn debug_pre_order<T: Debug>(f: &mut fmt::Formatter, head: Option<&BinaryNode<T>>) -> fmt::Result {
+    let mut list = f.debug_list();
+    let mut stack = Stack::new();
+    stack.push(head);
+
+    while let Some(link) = stack.pop() {
+        list.entry(&link.map(|node| &node.value));
+        if let Some(node) = link {
+            stack.push(node.right.as_deref());
+            stack.push(node.left.as_deref());
+        }
     }
 
-    compare(&a.left, &b.left) && compare(&a.right, &b.right)
+    list.finish()
+}
+
+impl<T: Debug> Debug for BinaryTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        debug_pre_order(f, self.head.as_deref())
+    }
+}
+
+impl<T: Debug> Debug for BinaryNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        debug_pre_order(f, Some(self))
+    }
 }
 
 impl<T> BinaryNode<T> {
@@ -73,138 +128,280 @@ impl<T> BinaryNode<T> {
     }
 }
 
-fn pre_order_walk(curr: Option<Box<BinaryNode<isize>>>, path: &mut Vec<isize>) {
-    if let Some(node) = curr {
-        path.push(node.value);
-        pre_order_walk(node.left, path);
-        pre_order_walk(node.right, path);
+pub struct PreOrder<'a, T> {
+    stack: Stack<&'a BinaryNode<T>>,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if let Some(right) = node.right.as_deref() {
+            self.stack.push(right);
+        }
+        if let Some(left) = node.left.as_deref() {
+            self.stack.push(left);
+        }
+        Some(&node.value)
     }
 }
 
-pub fn pre_order_search(tree: BinaryTree<isize>) -> Vec<isize> {
-    let mut path = Vec::new();
-    pre_order_walk(tree.head, &mut path);
-    path
+pub struct InOrder<'a, T> {
+    stack: Stack<&'a BinaryNode<T>>,
+    curr: Option<&'a BinaryNode<T>>,
 }
 
-fn in_order_walk(curr: Option<Box<BinaryNode<isize>>>, path: &mut Vec<isize>) {
-    if let Some(node) = curr {
-        in_order_walk(node.left, path);
-        path.push(node.value);
-        in_order_walk(node.right, path);
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.curr {
+            self.stack.push(node);
+            self.curr = node.left.as_deref();
+        }
+
+        let node = self.stack.pop()?;
+        self.curr = node.right.as_deref();
+        Some(&node.value)
     }
 }
 
-pub fn in_order_search(tree: BinaryTree<isize>) -> Vec<isize> {
-    let mut path = Vec::new();
-    in_order_walk(tree.head, &mut path);
-    path
+pub struct PostOrder<'a, T> {
+    // the flag says whether the children are already on the stack
+    stack: Stack<(&'a BinaryNode<T>, bool)>,
 }
 
-fn post_order_walk(curr: Option<Box<BinaryNode<isize>>>, path: &mut Vec<isize>) {
-    if let Some(node) = curr {
-        post_order_walk(node.left, path);
-        post_order_walk(node.right, path);
-        path.push(node.value);
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(&node.value);
+            }
+
+            self.stack.push((node, true));
+            if let Some(right) = node.right.as_deref() {
+                self.stack.push((right, false));
+            }
+            if let Some(left) = node.left.as_deref() {
+                self.stack.push((left, false));
+            }
+        }
     }
 }
 
-pub fn post_order_search(tree: BinaryTree<isize>) -> Vec<isize> {
-    let mut path = Vec::new();
-    post_order_walk(tree.head, &mut path);
-    path
+pub struct LevelOrder<'a, T> {
+    queue: Queue<&'a BinaryNode<T>>,
 }
 
-pub fn breadth_first_search(tree: BinaryTree<isize>, needle: isize) -> bool {
-    if let Some(head) = tree.head {
-        let mut queue = Queue::new();
-        queue.enqueue(head);
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
 
-        while queue.lenght() > 0 {
-            let curr = queue.deque().unwrap();
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.deque()?;
+        if let Some(left) = node.left.as_deref() {
+            self.queue.enqueue(left);
+        }
+        if let Some(right) = node.right.as_deref() {
+            self.queue.enqueue(right);
+        }
+        Some(&node.value)
+    }
+}
 
-            if curr.value == needle {
-                return true;
-            }
+impl<T> BinaryTree<T> {
+    pub fn pre_order(&self) -> PreOrder<'_, T> {
+        let mut stack = Stack::new();
+        if let Some(head) = self.head.as_deref() {
+            stack.push(head);
+        }
+        PreOrder { stack }
+    }
+
+    pub fn in_order(&self) -> InOrder<'_, T> {
+        InOrder {
+            stack: Stack::new(),
+            curr: self.head.as_deref(),
+        }
+    }
 
-            if let Some(left) = curr.left {
-                queue.enqueue(left);
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        let mut stack = Stack::new();
+        if let Some(head) = self.head.as_deref() {
+            stack.push((head, false));
+        }
+        PostOrder { stack }
+    }
+
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        let mut queue = Queue::new();
+        if let Some(head) = self.head.as_deref() {
+            queue.enqueue(head);
+        }
+        LevelOrder { queue }
+    }
+}
+
+// boxes would otherwise drop each other recursively, one frame per level
+impl<T> Drop for BinaryTree<T> {
+    fn drop(&mut self) {
+        let mut stack = Stack::new();
+        if let Some(head) = self.head.take() {
+            stack.push(head);
+        }
+
+        while let Some(mut node) = stack.pop() {
+            if let Some(left) = node.left.take() {
+                stack.push(left);
             }
-            if let Some(right) = curr.right {
-                queue.enqueue(right);
+            if let Some(right) = node.right.take() {
+                stack.push(right);
             }
         }
     }
+}
 
-    false
+pub fn pre_order_search<T: Clone>(tree: &BinaryTree<T>) -> Vec<T> {
+    tree.pre_order().cloned().collect()
+}
+
+pub fn in_order_search<T: Clone>(tree: &BinaryTree<T>) -> Vec<T> {
+    tree.in_order().cloned().collect()
+}
+
+pub fn post_order_search<T: Clone>(tree: &BinaryTree<T>) -> Vec<T> {
+    tree.post_order().cloned().collect()
+}
+
+pub fn breadth_first_search<T: PartialEq>(tree: &BinaryTree<T>, needle: T) -> bool {
+    tree.level_order().any(|value| *value == needle)
 }
 
-pub fn dfs_recursion(curr: Option<Box<BinaryNode<isize>>>, needle: isize) -> bool {
-    println!("curr: {:?}", curr);
+pub fn depth_first_search<T: PartialOrd>(tree: &BinaryTree<T>, needle: T) -> bool {
+    let mut curr = tree.head.as_deref();
 
-    if let Some(node) = curr {
+    while let Some(node) = curr {
         if node.value == needle {
             return true;
         }
 
-        if node.value < needle {
-            return dfs_recursion(node.right, needle);
-        }
-
-        return dfs_recursion(node.left, needle);
+        curr = if node.value < needle {
+            node.right.as_deref()
+        } else {
+            node.left.as_deref()
+        };
     }
 
     false
 }
 
-pub fn depth_first_search(tree: BinaryTree<isize>, needle: isize) -> bool {
-    dfs_recursion(tree.head, needle)
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::tree_render::diff;
 
     #[test]
     fn test_binary_tree() {
         let tree = crate::tests::tree::tree();
         assert_eq!(
-            pre_order_search(tree.clone()),
+            pre_order_search(&tree),
             vec![20, 50, 100, 30, 45, 29, 10, 15, 5, 7]
         );
         assert_eq!(
-            in_order_search(tree.clone()),
+            in_order_search(&tree),
             vec![100, 50, 45, 30, 29, 20, 15, 10, 7, 5]
         );
         assert_eq!(
-            post_order_search(tree),
+            post_order_search(&tree),
             vec![100, 45, 29, 30, 50, 15, 7, 5, 10, 20]
         );
     }
 
+    #[test]
+    fn test_iterators() {
+        let tree = crate::tests::tree::tree();
+        assert_eq!(
+            tree.level_order().copied().collect::<Vec<_>>(),
+            vec![20, 50, 10, 100, 30, 15, 5, 45, 29, 7]
+        );
+        assert_eq!(tree.pre_order().max(), Some(&100));
+        assert_eq!(tree.in_order().take(3).sum::<isize>(), 195);
+        assert_eq!(tree.post_order().position(|&v| v == 20), Some(9));
+
+        let empty: BinaryTree<isize> = BinaryTree::new();
+        assert_eq!(empty.pre_order().next(), None);
+        assert_eq!(empty.in_order().next(), None);
+        assert_eq!(empty.post_order().next(), None);
+        assert_eq!(empty.level_order().next(), None);
+
+        let mut words = BinaryTree::new();
+        words.set_head(BinaryNode::new(
+            String::from("b"),
+            Some(BinaryNode::boxed(String::from("a"), None, None)),
+            Some(BinaryNode::boxed(String::from("c"), None, None)),
+        ));
+        assert_eq!(in_order_search(&words), vec!["a", "b", "c"]);
+        assert!(depth_first_search(&words, String::from("c")));
+        assert!(!breadth_first_search(&words, String::from("d")));
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        // a left spine this long would overflow a recursive walk
+        let len = 1_000_000;
+        let mut node = BinaryNode::new(0, None, None);
+        for value in 1..len {
+            node = BinaryNode::new(value, Some(Box::new(node)), None);
+        }
+        let mut tree = BinaryTree::new();
+        tree.set_head(node);
+
+        assert_eq!(tree.in_order().next(), Some(&0));
+        assert_eq!(tree.pre_order().count(), len);
+        assert_eq!(tree.post_order().last(), Some(&(len - 1)));
+        assert_eq!(tree.level_order().nth(len - 1), Some(&0));
+        // the spine is also a search tree, searched all the way down
+        assert!(depth_first_search(&tree, 0));
+        assert!(!depth_first_search(&tree, len));
+
+        let copy = tree.clone();
+        assert!(copy == tree);
+        let mut other = copy.clone();
+        other.head.as_mut().unwrap().value = len;
+        assert!(other != tree);
+        assert!(format!("{:?}", copy).starts_with(&format!(
+            "[Some({}), Some({})",
+            len - 1,
+            len - 2
+        )));
+    }
+
     #[test]
     fn test_bfs() {
         let tree = crate::tests::tree::tree();
-        assert_eq!(breadth_first_search(tree.clone(), 45), true);
-        assert_eq!(breadth_first_search(tree.clone(), 7), true);
-        assert_eq!(breadth_first_search(tree.clone(), 69), false);
+        assert_eq!(breadth_first_search(&tree, 45), true);
+        assert_eq!(breadth_first_search(&tree, 7), true);
+        assert_eq!(breadth_first_search(&tree, 69), false);
     }
 
     #[test]
     fn test_compare() {
         let tree = crate::tests::tree::tree();
         let tree2 = crate::tests::tree::tree();
-        assert_eq!(tree, tree2);
+        assert!(tree == tree2, "trees differ:\n{}", diff(&tree, &tree2));
 
         let tree3 = crate::tests::tree::tree2();
-        assert_ne!(tree, tree3);
+        assert!(tree != tree3, "trees match:\n{}", diff(&tree, &tree3));
     }
 
     #[test]
     fn test_dfs() {
         let tree = crate::tests::tree::sorted_tree();
-        assert_eq!(depth_first_search(tree.clone(), 15), true);
-        assert_eq!(depth_first_search(tree.clone(), 7), false);
-        assert_eq!(depth_first_search(tree.clone(), 69), false);
+        assert_eq!(depth_first_search(&tree, 15), true);
+        assert_eq!(depth_first_search(&tree, 7), false);
+        assert_eq!(depth_first_search(&tree, 69), false);
     }
 }
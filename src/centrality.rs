@@ -0,0 +1,286 @@
+use crate::{
+    adjacency_list::WeightedAdjacencyList, dijkstra_list::dijkstra_tree, min_heap::MinHeap,
+    queue::Queue, stack::Stack,
+};
+
+pub fn pagerank(
+    graph: &WeightedAdjacencyList,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Vec<f64> {
+    let len = graph.list.len();
+    if len == 0 {
+        return vec![];
+    }
+
+    let mut ranks = vec![1.0 / len as f64; len];
+
+    for _ in 0..max_iterations {
+        // rank held by vertices without out edges is spread over everyone
+        let dangling: f64 = (0..len)
+            .filter(|&v| graph.list[v].is_empty())
+            .map(|v| ranks[v])
+            .sum();
+        let base = (1.0 - damping) / len as f64 + damping * dangling / len as f64;
+        let mut next = vec![base; len];
+
+        for (from, edges) in graph.list.iter().enumerate() {
+            let share = damping * ranks[from] / edges.len() as f64;
+            for edge in edges.iter() {
+                next[edge.to] += share;
+            }
+        }
+
+        let change: f64 = next
+            .iter()
+            .zip(ranks.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        ranks = next;
+        if change < tolerance {
+            break;
+        }
+    }
+
+    ranks
+}
+
+// Brandes: accumulate dependencies from the farthest vertex back to the source
+fn accumulate(
+    source: usize,
+    order: &mut Stack<usize>,
+    preds: &[Vec<usize>],
+    sigma: &[f64],
+    centrality: &mut [f64],
+) {
+    let mut delta = vec![0.0; preds.len()];
+
+    while let Some(w) = order.pop() {
+        for &v in preds[w].iter() {
+            delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+        }
+        if w != source {
+            centrality[w] += delta[w];
+        }
+    }
+}
+
+pub fn betweenness(graph: &WeightedAdjacencyList) -> Vec<f64> {
+    let len = graph.list.len();
+    let mut centrality = vec![0.0; len];
+
+    for source in 0..len {
+        let mut order = Stack::new();
+        let mut preds = vec![vec![]; len];
+        let mut sigma = vec![0.0; len];
+        let mut dist = vec![None; len];
+        sigma[source] = 1.0;
+        dist[source] = Some(0);
+
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+
+        while let Some(v) = queue.deque() {
+            order.push(v);
+            let next_dist = dist[v].map(|d: usize| d + 1);
+
+            for edge in graph.list[v].iter() {
+                let w = edge.to;
+                if dist[w].is_none() {
+                    dist[w] = next_dist;
+                    queue.enqueue(w);
+                }
+                if dist[w] == next_dist {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        accumulate(source, &mut order, &preds, &sigma, &mut centrality);
+    }
+
+    centrality
+}
+
+pub fn weighted_betweenness(graph: &WeightedAdjacencyList) -> Vec<f64> {
+    let len = graph.list.len();
+    let mut centrality = vec![0.0; len];
+
+    for source in 0..len {
+        let mut order = Stack::new();
+        let mut preds = vec![vec![]; len];
+        let mut sigma = vec![0.0; len];
+        let mut dist = vec![usize::MAX; len];
+        let mut settled = vec![false; len];
+        sigma[source] = 1.0;
+        dist[source] = 0;
+
+        let mut heap = MinHeap::new();
+        heap.insert((0, source));
+
+        while let Some((d, v)) = heap.delete() {
+            if settled[v] || d > dist[v] {
+                continue;
+            }
+            settled[v] = true;
+            order.push(v);
+
+            for edge in graph.list[v].iter() {
+                let w = edge.to;
+                let new_dist = d + edge.weight;
+
+                if new_dist < dist[w] {
+                    dist[w] = new_dist;
+                    sigma[w] = 0.0;
+                    preds[w].clear();
+                    heap.insert((new_dist, w));
+                }
+                if new_dist == dist[w] && !settled[w] {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        accumulate(source, &mut order, &preds, &sigma, &mut centrality);
+    }
+
+    centrality
+}
+
+fn distances(graph: &WeightedAdjacencyList, source: usize) -> impl Iterator<Item = usize> {
+    dijkstra_tree(source, graph)
+        .dists
+        .into_iter()
+        .enumerate()
+        .filter(move |&(v, d)| v != source && d != usize::MAX)
+        .map(|(_, d)| d)
+}
+
+pub fn closeness(graph: &WeightedAdjacencyList) -> Vec<f64> {
+    (0..graph.list.len())
+        .map(|v| {
+            let (reached, total) = distances(graph, v).fold((0, 0), |(n, sum), d| (n + 1, sum + d));
+            if total == 0 {
+                0.0
+            } else {
+                reached as f64 / total as f64
+            }
+        })
+        .collect()
+}
+
+pub fn harmonic(graph: &WeightedAdjacencyList) -> Vec<f64> {
+    (0..graph.list.len())
+        .map(|v| {
+            distances(graph, v)
+                .filter(|&d| d > 0)
+                .map(|d| 1.0 / d as f64)
+                .sum()
+        })
+        .collect()
+}
+
+pub fn out_degrees(graph: &WeightedAdjacencyList) -> Vec<usize> {
+    graph.list.iter().map(|edges| edges.len()).collect()
+}
+
+pub fn in_degrees(graph: &WeightedAdjacencyList) -> Vec<usize> {
+    let mut degrees = vec![0; graph.list.len()];
+    for edges in graph.list.iter() {
+        for edge in edges.iter() {
+            degrees[edge.to] += 1;
+        }
+    }
+    degrees
+}
+
+// how many vertices have each degree, indexed by degree
+pub fn degree_distribution(degrees: &[usize]) -> Vec<usize> {
+    let mut distribution = vec![0; degrees.iter().max().map_or(0, |&max| max + 1)];
+    for &degree in degrees {
+        distribution[degree] += 1;
+    }
+    distribution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn graph(len: usize, edges: &[(usize, usize, usize)]) -> WeightedAdjacencyList {
+        let mut list = vec![vec![]; len];
+        for &(from, to, weight) in edges {
+            list[from].push(GraphEdge { to, weight });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-6, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_pagerank() {
+        // a cycle spreads rank evenly
+        let cycle = graph(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+        assert_close(&pagerank(&cycle, 0.85, 1e-9, 100), &[0.25; 4]);
+
+        // everyone points at 0, which points nowhere
+        let star = graph(4, &[(1, 0, 1), (2, 0, 1), (3, 0, 1)]);
+        let ranks = pagerank(&star, 0.85, 1e-9, 100);
+        assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        assert!(ranks[0] > ranks[1]);
+        assert_close(&ranks[1..], &[ranks[1]; 3]);
+    }
+
+    #[test]
+    fn test_betweenness() {
+        // (0) - (1) - (2) - (3), listed both ways
+        let path = graph(
+            4,
+            &[
+                (0, 1, 1),
+                (1, 0, 1),
+                (1, 2, 1),
+                (2, 1, 1),
+                (2, 3, 1),
+                (3, 2, 1),
+            ],
+        );
+        assert_close(&betweenness(&path), &[0.0, 4.0, 4.0, 0.0]);
+        assert_close(&weighted_betweenness(&path), &[0.0, 4.0, 4.0, 0.0]);
+
+        // two equal ways from 0 to 3, but 0 -> 2 is heavy
+        let diamond = graph(4, &[(0, 1, 1), (0, 2, 5), (1, 3, 1), (2, 3, 1)]);
+        assert_close(&betweenness(&diamond), &[0.0, 0.5, 0.5, 0.0]);
+        assert_close(&weighted_betweenness(&diamond), &[0.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_closeness() {
+        let diamond = graph(4, &[(0, 1, 1), (0, 2, 5), (1, 3, 1), (2, 3, 1)]);
+        assert_close(&closeness(&diamond), &[3.0 / 8.0, 1.0, 1.0, 0.0]);
+        assert_close(
+            &harmonic(&diamond),
+            &[1.0 + 1.0 / 5.0 + 1.0 / 2.0, 1.0, 1.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn test_degrees() {
+        let graph = crate::tests::graph::directed_list();
+        assert_eq!(out_degrees(&graph), vec![2, 1, 1, 0, 3, 2, 1]);
+        assert_eq!(in_degrees(&graph), vec![0, 2, 2, 3, 1, 1, 1]);
+        assert_eq!(degree_distribution(&out_degrees(&graph)), vec![1, 3, 2, 1]);
+        assert_eq!(degree_distribution(&in_degrees(&graph)), vec![1, 3, 2, 1]);
+        assert_eq!(degree_distribution(&[]), vec![]);
+    }
+}
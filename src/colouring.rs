@@ -0,0 +1,365 @@
+use crate::adjacency_matrix::WeightedAdjacencyMatrix;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Colouring {
+    pub colours: Vec<usize>,
+    pub count: usize,
+}
+
+// edges count in either direction and self loops are ignored
+fn adjacency(graph: &WeightedAdjacencyMatrix) -> Vec<Vec<bool>> {
+    let len = graph.matrix.len();
+    (0..len)
+        .map(|u| {
+            (0..len)
+                .map(|v| u != v && (graph.matrix[u][v].is_some() || graph.matrix[v][u].is_some()))
+                .collect()
+        })
+        .collect()
+}
+
+fn degrees(adjacent: &[Vec<bool>]) -> Vec<usize> {
+    adjacent
+        .iter()
+        .map(|row| row.iter().filter(|&&edge| edge).count())
+        .collect()
+}
+
+fn smallest_free(adjacent: &[bool], colours: &[Option<usize>]) -> usize {
+    let mut used: Vec<usize> = adjacent
+        .iter()
+        .zip(colours.iter())
+        .filter_map(|(&edge, colour)| if edge { *colour } else { None })
+        .collect();
+    used.sort_unstable();
+    used.dedup();
+
+    used.iter()
+        .enumerate()
+        .find(|&(i, &colour)| i != colour)
+        .map_or(used.len(), |(i, _)| i)
+}
+
+fn finish(colours: Vec<Option<usize>>) -> Colouring {
+    let colours: Vec<usize> = colours.into_iter().map(|colour| colour.unwrap()).collect();
+    let count = colours.iter().max().map_or(0, |max| max + 1);
+    Colouring { colours, count }
+}
+
+// colours vertices in the given order, each with the smallest colour its
+// already coloured neighbours leave free
+pub fn greedy(graph: &WeightedAdjacencyMatrix, order: &[usize]) -> Colouring {
+    let adjacent = adjacency(graph);
+    let mut colours = vec![None; adjacent.len()];
+
+    for &v in order {
+        colours[v] = Some(smallest_free(&adjacent[v], &colours));
+    }
+
+    finish(colours)
+}
+
+pub fn welsh_powell(graph: &WeightedAdjacencyMatrix) -> Colouring {
+    let degrees = degrees(&adjacency(graph));
+    let mut order: Vec<usize> = (0..degrees.len()).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(degrees[v]));
+    greedy(graph, &order)
+}
+
+// next vertex is the one seeing the most distinct colours, ties broken by
+// uncoloured degree
+pub fn dsatur(graph: &WeightedAdjacencyMatrix) -> Colouring {
+    let adjacent = adjacency(graph);
+    let len = adjacent.len();
+    let mut colours: Vec<Option<usize>> = vec![None; len];
+    let mut seen: Vec<Vec<bool>> = vec![vec![]; len];
+
+    for _ in 0..len {
+        let v = (0..len)
+            .filter(|&v| colours[v].is_none())
+            .max_by_key(|&v| {
+                let saturation = seen[v].iter().filter(|&&s| s).count();
+                let degree = (0..len)
+                    .filter(|&u| adjacent[v][u] && colours[u].is_none())
+                    .count();
+                (saturation, degree, std::cmp::Reverse(v))
+            })
+            .unwrap();
+
+        let colour = smallest_free(&adjacent[v], &colours);
+        colours[v] = Some(colour);
+
+        for (seen, _) in seen
+            .iter_mut()
+            .zip(adjacent[v].iter())
+            .filter(|(_, &edge)| edge)
+        {
+            if seen.len() <= colour {
+                seen.resize(colour + 1, false);
+            }
+            seen[colour] = true;
+        }
+    }
+
+    finish(colours)
+}
+
+fn colour_with(
+    adjacent: &[Vec<bool>],
+    order: &[usize],
+    k: usize,
+    colours: &mut [Option<usize>],
+) -> bool {
+    let Some((&v, rest)) = order.split_first() else {
+        return true;
+    };
+
+    for colour in 0..k {
+        let clash = (0..adjacent.len()).any(|u| adjacent[v][u] && colours[u] == Some(colour));
+        if clash {
+            continue;
+        }
+
+        colours[v] = Some(colour);
+        if colour_with(adjacent, rest, k, colours) {
+            return true;
+        }
+        colours[v] = None;
+
+        // a colour nobody has used yet is as good as any other unused one
+        if !colours.contains(&Some(colour)) {
+            break;
+        }
+    }
+
+    false
+}
+
+// exact, by backtracking between the largest clique and the DSatur bound;
+// only meant for small graphs
+pub fn chromatic_number(graph: &WeightedAdjacencyMatrix) -> Colouring {
+    let upper = dsatur(graph);
+    let lower = maximum_clique(graph).len();
+    let adjacent = adjacency(graph);
+
+    let degrees = degrees(&adjacent);
+    let mut order: Vec<usize> = (0..adjacent.len()).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(degrees[v]));
+
+    for k in lower..upper.count {
+        let mut colours = vec![None; adjacent.len()];
+        if colour_with(&adjacent, &order, k, &mut colours) {
+            return finish(colours);
+        }
+    }
+
+    upper
+}
+
+fn bron_kerbosch_pivot(
+    adjacent: &[Vec<bool>],
+    clique: &mut Vec<usize>,
+    mut candidates: Vec<usize>,
+    mut excluded: Vec<usize>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            out.push(clique.clone());
+        }
+        return;
+    }
+
+    // the pivot with most candidate neighbours leaves the fewest branches
+    let pivot = candidates
+        .iter()
+        .chain(excluded.iter())
+        .copied()
+        .max_by_key(|&u| candidates.iter().filter(|&&v| adjacent[u][v]).count())
+        .unwrap();
+    let branches: Vec<usize> = candidates
+        .iter()
+        .copied()
+        .filter(|&v| !adjacent[pivot][v])
+        .collect();
+
+    for v in branches {
+        clique.push(v);
+        bron_kerbosch_pivot(
+            adjacent,
+            clique,
+            candidates
+                .iter()
+                .copied()
+                .filter(|&u| adjacent[v][u])
+                .collect(),
+            excluded
+                .iter()
+                .copied()
+                .filter(|&u| adjacent[v][u])
+                .collect(),
+            out,
+        );
+        clique.pop();
+
+        candidates.retain(|&u| u != v);
+        excluded.push(v);
+    }
+}
+
+fn cliques(adjacent: &[Vec<bool>]) -> Vec<Vec<usize>> {
+    let mut out = vec![];
+    bron_kerbosch_pivot(
+        adjacent,
+        &mut vec![],
+        (0..adjacent.len()).collect(),
+        vec![],
+        &mut out,
+    );
+
+    for clique in out.iter_mut() {
+        clique.sort_unstable();
+    }
+    out.sort();
+    out
+}
+
+pub fn maximal_cliques(graph: &WeightedAdjacencyMatrix) -> Vec<Vec<usize>> {
+    cliques(&adjacency(graph))
+}
+
+// the first of the largest sets, so ties resolve to the smallest vertices
+fn largest(sets: Vec<Vec<usize>>) -> Vec<usize> {
+    sets.into_iter()
+        .min_by_key(|set| std::cmp::Reverse(set.len()))
+        .unwrap_or_default()
+}
+
+pub fn maximum_clique(graph: &WeightedAdjacencyMatrix) -> Vec<usize> {
+    largest(maximal_cliques(graph))
+}
+
+// a clique in the complement graph
+pub fn maximum_independent_set(graph: &WeightedAdjacencyMatrix) -> Vec<usize> {
+    let complement: Vec<Vec<bool>> = adjacency(graph)
+        .iter()
+        .enumerate()
+        .map(|(u, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(v, &edge)| u != v && !edge)
+                .collect()
+        })
+        .collect();
+
+    largest(cliques(&complement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(len: usize, edges: &[(usize, usize)]) -> WeightedAdjacencyMatrix {
+        let mut matrix = vec![vec![None; len]; len];
+        for &(from, to) in edges {
+            matrix[from][to] = Some(1);
+        }
+
+        WeightedAdjacencyMatrix { matrix }
+    }
+
+    fn assert_proper(graph: &WeightedAdjacencyMatrix, colouring: &Colouring) {
+        let adjacent = adjacency(graph);
+        for (u, row) in adjacent.iter().enumerate() {
+            assert!(colouring.colours[u] < colouring.count);
+            for (v, &edge) in row.iter().enumerate() {
+                if edge {
+                    assert_ne!(colouring.colours[u], colouring.colours[v]);
+                }
+            }
+        }
+    }
+
+    // the five cycle 0..5 joined to the inner pentagram 5..10
+    fn petersen() -> WeightedAdjacencyMatrix {
+        let mut edges = vec![];
+        for i in 0..5 {
+            edges.push((i, (i + 1) % 5));
+            edges.push((i, i + 5));
+            edges.push((i + 5, (i + 2) % 5 + 5));
+        }
+        graph(10, &edges)
+    }
+
+    #[test]
+    fn test_greedy_colourings() {
+        // a crown graph: greedy in the natural order needs four colours
+        let crown = graph(
+            8,
+            &[
+                (0, 3),
+                (0, 5),
+                (0, 7),
+                (2, 1),
+                (2, 5),
+                (2, 7),
+                (4, 1),
+                (4, 3),
+                (4, 7),
+                (6, 1),
+                (6, 3),
+                (6, 5),
+            ],
+        );
+        let natural = greedy(&crown, &(0..8).collect::<Vec<_>>());
+        assert_proper(&crown, &natural);
+        assert_eq!(natural.count, 4);
+
+        for colouring in [welsh_powell(&crown), dsatur(&crown)] {
+            assert_proper(&crown, &colouring);
+        }
+        assert_eq!(dsatur(&crown).count, 2);
+
+        let petersen = petersen();
+        for colouring in [welsh_powell(&petersen), dsatur(&petersen)] {
+            assert_proper(&petersen, &colouring);
+            assert!(colouring.count <= 4);
+        }
+    }
+
+    #[test]
+    fn test_chromatic_number() {
+        let petersen = petersen();
+        let colouring = chromatic_number(&petersen);
+        assert_proper(&petersen, &colouring);
+        assert_eq!(colouring.count, 3);
+
+        let odd_cycle = graph(7, &(0..7).map(|i| (i, (i + 1) % 7)).collect::<Vec<_>>());
+        assert_eq!(chromatic_number(&odd_cycle).count, 3);
+
+        let complete = graph(
+            5,
+            &(0..5)
+                .flat_map(|u| (u + 1..5).map(move |v| (u, v)))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(chromatic_number(&complete).count, 5);
+        assert_eq!(chromatic_number(&graph(3, &[])).count, 1);
+        assert_eq!(chromatic_number(&graph(0, &[])).count, 0);
+    }
+
+    #[test]
+    fn test_cliques() {
+        // two triangles sharing the edge 1 - 2, plus a pendant 3 - 4
+        let graph = graph(5, &[(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (3, 4)]);
+        assert_eq!(
+            maximal_cliques(&graph),
+            vec![vec![0, 1, 2], vec![1, 2, 3], vec![3, 4]]
+        );
+        assert_eq!(maximum_clique(&graph), vec![0, 1, 2]);
+        assert_eq!(maximum_independent_set(&graph), vec![0, 3]);
+
+        assert_eq!(maximum_clique(&petersen()).len(), 2);
+        assert_eq!(maximum_independent_set(&petersen()).len(), 4);
+    }
+}
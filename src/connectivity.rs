@@ -0,0 +1,265 @@
+use crate::{adjacency_list::WeightedAdjacencyList, queue::Queue, scc::Components, stack::Stack};
+
+#[derive(Clone, Debug)]
+pub struct LowLink {
+    pub bridges: Vec<(usize, usize)>,
+    pub articulation_points: Vec<usize>,
+    pub biconnected_components: Vec<Vec<(usize, usize)>>,
+}
+
+fn ordered(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+pub fn connected_components(graph: &WeightedAdjacencyList) -> Components {
+    let list = graph.undirected().list;
+    let mut ids = vec![None; list.len()];
+    let mut count = 0;
+
+    for root in 0..list.len() {
+        if ids[root].is_some() {
+            continue;
+        }
+
+        ids[root] = Some(count);
+        let mut queue = Queue::new();
+        queue.enqueue(root);
+
+        while let Some(curr) = queue.deque() {
+            for edge in list[curr].iter() {
+                if ids[edge.to].is_none() {
+                    ids[edge.to] = Some(count);
+                    queue.enqueue(edge.to);
+                }
+            }
+        }
+
+        count += 1;
+    }
+
+    Components {
+        count,
+        ids: ids.into_iter().map(|id| id.unwrap()).collect(),
+    }
+}
+
+// parallel edges are kept, so a pair joined twice is never a bridge
+pub fn low_link(graph: &WeightedAdjacencyList) -> LowLink {
+    let list = graph.undirected_multi().list;
+    let mut disc: Vec<Option<usize>> = vec![None; list.len()];
+    let mut low = vec![0; list.len()];
+    let mut is_articulation = vec![false; list.len()];
+    let mut edges = Stack::new();
+    let mut time = 0;
+
+    let mut bridges = vec![];
+    let mut biconnected_components = vec![];
+
+    for root in 0..list.len() {
+        if disc[root].is_some() {
+            continue;
+        }
+
+        disc[root] = Some(time);
+        low[root] = time;
+        time += 1;
+        let mut root_children = 0;
+
+        // (vertex, parent, next edge, parent edge already skipped)
+        let mut call = Stack::new();
+        call.push((root, None, 0, false));
+
+        while let Some(top) = call.peek_mut() {
+            let (curr, parent, i, skipped) = *top;
+
+            if i < list[curr].len() {
+                top.2 += 1;
+                let next = list[curr][i].to;
+
+                // only the edge we came in through is ignored, so a parallel
+                // edge back to the parent still counts as a back edge
+                if Some(next) == parent && !skipped {
+                    top.3 = true;
+                    continue;
+                }
+
+                match disc[next] {
+                    None => {
+                        disc[next] = Some(time);
+                        low[next] = time;
+                        time += 1;
+                        if curr == root {
+                            root_children += 1;
+                        }
+                        edges.push((curr, next));
+                        call.push((next, Some(curr), 0, false));
+                    }
+                    Some(next_disc) if next_disc < disc[curr].unwrap() => {
+                        low[curr] = low[curr].min(next_disc);
+                        edges.push((curr, next));
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            call.pop();
+            let Some(parent) = parent else {
+                continue;
+            };
+
+            low[parent] = low[parent].min(low[curr]);
+            let parent_disc = disc[parent].unwrap();
+
+            if low[curr] > parent_disc {
+                bridges.push(ordered(parent, curr));
+            }
+
+            if low[curr] >= parent_disc {
+                if parent != root {
+                    is_articulation[parent] = true;
+                }
+
+                let mut component = vec![];
+                while let Some(edge) = edges.pop() {
+                    component.push(ordered(edge.0, edge.1));
+                    if edge == (parent, curr) {
+                        break;
+                    }
+                }
+                component.sort();
+                biconnected_components.push(component);
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    bridges.sort();
+
+    LowLink {
+        bridges,
+        articulation_points: (0..list.len()).filter(|&v| is_articulation[v]).collect(),
+        biconnected_components,
+    }
+}
+
+pub fn bridges(graph: &WeightedAdjacencyList) -> Vec<(usize, usize)> {
+    low_link(graph).bridges
+}
+
+pub fn articulation_points(graph: &WeightedAdjacencyList) -> Vec<usize> {
+    low_link(graph).articulation_points
+}
+
+pub fn biconnected_components(graph: &WeightedAdjacencyList) -> Vec<Vec<(usize, usize)>> {
+    low_link(graph).biconnected_components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn bowtie() -> WeightedAdjacencyList {
+        // (0) --- (1)           (4)
+        //   \     /            /   \
+        //     (2) ---------- (3) --- (5) --- (6)      (7)
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (5, 6),
+        ];
+        let mut list = vec![vec![]; 8];
+        for (from, to) in edges {
+            list[from].push(GraphEdge { to, weight: 1 });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let components = connected_components(&bowtie());
+        assert_eq!(components.count, 2);
+        assert_eq!(
+            components.groups(),
+            vec![vec![0, 1, 2, 3, 4, 5, 6], vec![7]]
+        );
+
+        assert_eq!(
+            connected_components(&crate::tests::graph::directed_list()).count,
+            1
+        );
+        assert_eq!(
+            connected_components(&crate::tests::graph::undirected_list()).count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_low_link() {
+        let graph = bowtie();
+        assert_eq!(bridges(&graph), vec![(2, 3), (5, 6)]);
+        assert_eq!(articulation_points(&graph), vec![2, 3, 5]);
+
+        let mut components = biconnected_components(&graph);
+        components.sort();
+        assert_eq!(
+            components,
+            vec![
+                vec![(0, 1), (0, 2), (1, 2)],
+                vec![(2, 3)],
+                vec![(3, 4), (3, 5), (4, 5)],
+                vec![(5, 6)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_low_link_fixtures() {
+        // both fixtures survive the loss of any single vertex or edge
+        for graph in [
+            crate::tests::graph::directed_list(),
+            crate::tests::graph::undirected_list(),
+        ] {
+            let result = low_link(&graph);
+            assert_eq!(result.bridges, vec![]);
+            assert_eq!(result.articulation_points, vec![]);
+            assert_eq!(result.biconnected_components.len(), 1);
+        }
+
+        // without 0 -> 1 and 2 -> 3 only the 3-4-5-6 cycle is left, with
+        // (0) - (2) and (1) hanging off it
+        let mut graph = crate::tests::graph::directed_list();
+        graph.list[0].remove(0);
+        graph.list[2].clear();
+        let result = low_link(&graph);
+        assert_eq!(result.bridges, vec![(0, 2), (1, 4), (2, 5)]);
+        assert_eq!(result.articulation_points, vec![2, 4, 5]);
+        assert_eq!(result.biconnected_components.len(), 4);
+    }
+
+    #[test]
+    fn test_parallel_edges() {
+        // (0) === (1) --- (2), the doubled edge listed twice one way
+        let mut graph = WeightedAdjacencyList {
+            list: vec![vec![]; 3],
+        };
+        for (from, to) in [(0, 1), (0, 1), (1, 2), (2, 1)] {
+            graph.list[from].push(GraphEdge { to, weight: 1 });
+        }
+
+        let result = low_link(&graph);
+        assert_eq!(result.bridges, vec![(1, 2)]);
+        assert_eq!(result.articulation_points, vec![1]);
+        assert_eq!(result.biconnected_components.len(), 2);
+    }
+}
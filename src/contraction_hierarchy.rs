@@ -0,0 +1,574 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, Write},
+    path::Path,
+};
+
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    csr::{ensure_remaining, read_count, read_u32s, write_u32s, CsrGraph, Error},
+    min_heap::MinHeap,
+};
+
+const MAGIC: &[u8; 4] = b"CHGR";
+const VERSION: u32 = 1;
+const NO_MIDDLE: u32 = u32::MAX;
+
+// witness searches give up after settling this many vertices; stopping early
+// only costs an unneeded shortcut, never a wrong distance
+const WITNESS_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractionHierarchy {
+    // position of each vertex in the contraction order
+    pub rank: Vec<u32>,
+    // edges towards higher ranked vertices, by their tail
+    pub up: CsrGraph,
+    // edges coming down from higher ranked vertices, by their head, pointing
+    // back at the tail so a backward search also climbs
+    pub down: CsrGraph,
+    // the vertex a shortcut skips over, NO_MIDDLE for original edges
+    pub up_middles: Vec<u32>,
+    pub down_middles: Vec<u32>,
+}
+
+// the part of the graph not contracted yet, one edge per ordered pair
+struct Remaining {
+    out: Vec<BTreeMap<usize, (usize, u32)>>,
+    inc: Vec<BTreeMap<usize, (usize, u32)>>,
+}
+
+impl Remaining {
+    fn new(graph: &WeightedAdjacencyList) -> Self {
+        let len = graph.list.len();
+        let mut remaining = Remaining {
+            out: vec![BTreeMap::new(); len],
+            inc: vec![BTreeMap::new(); len],
+        };
+
+        for (from, edges) in graph.list.iter().enumerate() {
+            for edge in edges.iter().filter(|edge| edge.to != from) {
+                remaining.add(from, edge.to, edge.weight, NO_MIDDLE);
+            }
+        }
+
+        remaining
+    }
+
+    // keeps whichever of the old and new edge is cheaper
+    fn add(&mut self, from: usize, to: usize, weight: usize, middle: u32) {
+        if self.out[from]
+            .get(&to)
+            .is_some_and(|&(old, _)| old <= weight)
+        {
+            return;
+        }
+        self.out[from].insert(to, (weight, middle));
+        self.inc[to].insert(from, (weight, middle));
+    }
+
+    // distances from source that avoid `skip`, as far as `limit`
+    fn witness(&self, source: usize, skip: usize, limit: usize) -> HashMap<usize, usize> {
+        let mut dists = HashMap::from([(source, 0)]);
+        let mut heap = MinHeap::new();
+        heap.insert((0, source));
+        let mut settled = 0;
+
+        while let Some((dist, curr)) = heap.delete() {
+            if dist > limit || settled >= WITNESS_LIMIT {
+                break;
+            }
+            if dist > dists[&curr] {
+                continue;
+            }
+            settled += 1;
+
+            for (&to, &(weight, _)) in self.out[curr].iter() {
+                let new_dist = dist + weight;
+                if to != skip && dists.get(&to).is_none_or(|&d| new_dist < d) {
+                    dists.insert(to, new_dist);
+                    heap.insert((new_dist, to));
+                }
+            }
+        }
+
+        dists
+    }
+
+    // shortcuts u -> w needed to keep distances once v is gone
+    fn shortcuts(&self, v: usize) -> Vec<(usize, usize, usize)> {
+        let mut out = vec![];
+        let longest_out = self.out[v].values().map(|&(weight, _)| weight).max();
+        let Some(longest_out) = longest_out else {
+            return out;
+        };
+
+        for (&u, &(to_v, _)) in self.inc[v].iter() {
+            let dists = self.witness(u, v, to_v + longest_out);
+
+            for (&w, &(from_v, _)) in self.out[v].iter() {
+                let via = to_v + from_v;
+                if w != u && dists.get(&w).is_none_or(|&d| d > via) {
+                    out.push((u, w, via));
+                }
+            }
+        }
+
+        out
+    }
+
+    fn edge_difference(&self, v: usize) -> i64 {
+        self.shortcuts(v).len() as i64 - (self.out[v].len() + self.inc[v].len()) as i64
+    }
+}
+
+// rows of (to, weight, middle) as a csr graph plus its middles in edge order
+fn to_csr(rows: Vec<Vec<(usize, usize, u32)>>) -> Result<(CsrGraph, Vec<u32>), Error> {
+    let middles = rows
+        .iter()
+        .flatten()
+        .map(|&(_, _, middle)| middle)
+        .collect();
+    let list = WeightedAdjacencyList {
+        list: rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(to, weight, _)| GraphEdge { to, weight })
+                    .collect()
+            })
+            .collect(),
+    };
+
+    Ok((CsrGraph::try_from(&list)?, middles))
+}
+
+fn middle_of(graph: &CsrGraph, middles: &[u32], vertex: usize, target: usize) -> Option<usize> {
+    let start = graph.offsets[vertex] as usize;
+    let end = graph.offsets[vertex + 1] as usize;
+    let i = (start..end).find(|&i| graph.targets[i] as usize == target)?;
+
+    match middles[i] {
+        NO_MIDDLE => None,
+        middle => Some(middle as usize),
+    }
+}
+
+// vertex -> (distance, previous vertex and the middle of the edge used)
+type SearchSpace = HashMap<usize, (usize, Option<(usize, u32)>)>;
+
+fn upward(graph: &CsrGraph, middles: &[u32], source: usize) -> SearchSpace {
+    let mut space: SearchSpace = HashMap::from([(source, (0, None))]);
+    let mut heap = MinHeap::new();
+    heap.insert((0, source));
+
+    while let Some((dist, curr)) = heap.delete() {
+        if dist > space[&curr].0 {
+            continue;
+        }
+
+        let start = graph.offsets[curr] as usize;
+        for (i, (to, weight)) in graph.edges(curr).enumerate() {
+            let new_dist = dist + weight;
+            if space.get(&to).is_none_or(|&(d, _)| new_dist < d) {
+                space.insert(to, (new_dist, Some((curr, middles[start + i]))));
+                heap.insert((new_dist, to));
+            }
+        }
+    }
+
+    space
+}
+
+impl ContractionHierarchy {
+    // fails when a weight, or a shortcut summing several, does not fit the
+    // u32 weights of the csr arrays
+    pub fn new(graph: &WeightedAdjacencyList) -> Result<Self, Error> {
+        // checked up front so the sums below stay far from overflowing
+        if graph
+            .list
+            .iter()
+            .flatten()
+            .any(|edge| edge.weight > u32::MAX as usize)
+        {
+            return Err(Error::TooLarge("weight"));
+        }
+
+        let len = graph.list.len();
+        let mut remaining = Remaining::new(graph);
+        let mut rank = vec![u32::MAX; len];
+        let mut up = vec![vec![]; len];
+        let mut down = vec![vec![]; len];
+
+        let mut heap = MinHeap::new();
+        for v in 0..len {
+            heap.insert((remaining.edge_difference(v), v));
+        }
+
+        let mut next = 0;
+        while let Some((priority, v)) = heap.delete() {
+            if rank[v] != u32::MAX {
+                continue;
+            }
+
+            // priorities go stale as neighbours are contracted, recheck lazily
+            let current = remaining.edge_difference(v);
+            if current > priority {
+                heap.insert((current, v));
+                continue;
+            }
+
+            for (u, w, weight) in remaining.shortcuts(v) {
+                remaining.add(u, w, weight, v as u32);
+            }
+
+            rank[v] = next;
+            next += 1;
+
+            for (&w, &(weight, middle)) in remaining.out[v].iter() {
+                up[v].push((w, weight, middle));
+                remaining.inc[w].remove(&v);
+            }
+            for (&u, &(weight, middle)) in remaining.inc[v].iter() {
+                down[v].push((u, weight, middle));
+                remaining.out[u].remove(&v);
+            }
+            remaining.out[v].clear();
+            remaining.inc[v].clear();
+        }
+
+        let (up, up_middles) = to_csr(up)?;
+        let (down, down_middles) = to_csr(down)?;
+
+        Ok(ContractionHierarchy {
+            rank,
+            up,
+            down,
+            up_middles,
+            down_middles,
+        })
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.rank.len()
+    }
+
+    // middle of the original or shortcut edge from -> to, if it is a shortcut
+    fn middle(&self, from: usize, to: usize) -> Option<usize> {
+        if self.rank[from] < self.rank[to] {
+            middle_of(&self.up, &self.up_middles, from, to)
+        } else {
+            middle_of(&self.down, &self.down_middles, to, from)
+        }
+    }
+
+    fn unpack(&self, from: usize, to: usize, middle: u32, out: &mut Vec<usize>) {
+        let mut stack = vec![(from, to, middle)];
+
+        while let Some((from, to, middle)) = stack.pop() {
+            if middle == NO_MIDDLE {
+                out.push(to);
+                continue;
+            }
+
+            let middle = middle as usize;
+            let second = self.middle(middle, to).map_or(NO_MIDDLE, |m| m as u32);
+            let first = self.middle(from, middle).map_or(NO_MIDDLE, |m| m as u32);
+            stack.push((middle, to, second));
+            stack.push((from, middle, first));
+        }
+    }
+
+    // distance and original path, found by two upward searches that meet at
+    // the highest vertex of the path
+    pub fn query(&self, source: usize, target: usize) -> Option<(usize, Vec<usize>)> {
+        let forward = upward(&self.up, &self.up_middles, source);
+        let backward = upward(&self.down, &self.down_middles, target);
+
+        let (dist, meet) = forward
+            .iter()
+            .filter_map(|(&v, &(f, _))| backward.get(&v).map(|&(b, _)| (f + b, v)))
+            .min()?;
+
+        let mut climb = vec![];
+        let mut curr = meet;
+        while let Some((prev, middle)) = forward[&curr].1 {
+            climb.push((prev, curr, middle));
+            curr = prev;
+        }
+
+        let mut out = vec![source];
+        for &(from, to, middle) in climb.iter().rev() {
+            self.unpack(from, to, middle, &mut out);
+        }
+
+        let mut curr = meet;
+        while let Some((next, middle)) = backward[&curr].1 {
+            self.unpack(curr, next, middle, &mut out);
+            curr = next;
+        }
+
+        Some((dist, out))
+    }
+
+    // same shape as `dijkstra_list`: empty when there is no path
+    pub fn shortest_path(&self, source: usize, target: usize) -> Vec<usize> {
+        if source == target || target >= self.vertex_count() {
+            return vec![];
+        }
+
+        self.query(source, target).map_or(vec![], |(_, path)| path)
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.vertex_count() as u64).to_le_bytes())?;
+        write_u32s(writer, &self.rank)?;
+        self.up.write_to(writer)?;
+        write_u32s(writer, &self.up_middles)?;
+        self.down.write_to(writer)?;
+        write_u32s(writer, &self.down_middles)?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> Result<Self, Error> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::InvalidFormat("not a contraction hierarchy file"));
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        if u32::from_le_bytes(version) != VERSION {
+            return Err(Error::InvalidFormat("unsupported version"));
+        }
+
+        // every count is checked against what is left of the input before
+        // anything is allocated for it
+        let vertices = read_count(reader, "vertex count too large")?;
+        ensure_remaining(reader, vertices as u64)?;
+        let rank = read_u32s(reader, vertices)?;
+        let up = CsrGraph::read_from(reader)?;
+        ensure_remaining(reader, up.edge_count() as u64)?;
+        let up_middles = read_u32s(reader, up.edge_count())?;
+        let down = CsrGraph::read_from(reader)?;
+        ensure_remaining(reader, down.edge_count() as u64)?;
+        let down_middles = read_u32s(reader, down.edge_count())?;
+
+        let mut seen = vec![false; vertices];
+        for &r in rank.iter() {
+            if r as usize >= vertices || std::mem::replace(&mut seen[r as usize], true) {
+                return Err(Error::InvalidFormat("rank is not a permutation"));
+            }
+        }
+        if up.vertex_count() != vertices || down.vertex_count() != vertices {
+            return Err(Error::InvalidFormat("vertex counts disagree"));
+        }
+        if up_middles
+            .iter()
+            .chain(down_middles.iter())
+            .any(|&m| m != NO_MIDDLE && m as usize >= vertices)
+        {
+            return Err(Error::InvalidFormat("shortcut middle out of range"));
+        }
+
+        // edges climb the order and a shortcut skips a vertex below both of
+        // its ends, so unpacking always reaches original edges
+        for (graph, middles) in [(&up, &up_middles), (&down, &down_middles)] {
+            for v in 0..vertices {
+                let start = graph.offsets[v] as usize;
+                for (i, (to, _)) in graph.edges(v).enumerate() {
+                    if rank[to] <= rank[v] {
+                        return Err(Error::InvalidFormat("edge does not climb the order"));
+                    }
+                    let middle = middles[start + i];
+                    if middle != NO_MIDDLE && rank[middle as usize] >= rank[v] {
+                        return Err(Error::InvalidFormat(
+                            "shortcut middle is not below its ends",
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(ContractionHierarchy {
+            rank,
+            up,
+            down,
+            up_middles,
+            down_middles,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::read_from(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{
+        dijkstra_list::{dijkstra_list, dijkstra_tree},
+        generators::{gnp, grid, random_geometric, Rng},
+    };
+
+    fn path_weight(graph: &WeightedAdjacencyList, path: &[usize]) -> usize {
+        path.windows(2)
+            .map(|w| {
+                graph.list[w[0]]
+                    .iter()
+                    .filter(|edge| edge.to == w[1])
+                    .map(|edge| edge.weight)
+                    .min()
+                    .unwrap()
+            })
+            .sum()
+    }
+
+    // every pair agrees with dijkstra on distance, and the unpacked path is a
+    // real path of that length, though ties may pick another one
+    fn assert_matches_dijkstra(graph: &WeightedAdjacencyList, ch: &ContractionHierarchy) {
+        for source in 0..graph.list.len() {
+            let tree = dijkstra_tree(source, graph);
+
+            for target in 0..graph.list.len() {
+                match ch.query(source, target) {
+                    None => assert_eq!(tree.dists[target], usize::MAX),
+                    Some((dist, path)) => {
+                        assert_eq!(dist, tree.dists[target]);
+                        assert_eq!((path[0], path[path.len() - 1]), (source, target));
+                        assert_eq!(path_weight(graph, &path), dist);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_contraction_hierarchy() {
+        let graph = crate::tests::graph::directed_list();
+        let ch = ContractionHierarchy::new(&graph).unwrap();
+        assert_matches_dijkstra(&graph, &ch);
+
+        for (source, target) in [(0, 6), (4, 2), (6, 0), (2, 2)] {
+            assert_eq!(
+                ch.shortest_path(source, target),
+                dijkstra_list(source, target, graph.clone())
+            );
+        }
+
+        let graph = crate::tests::graph::undirected_list();
+        let ch = ContractionHierarchy::new(&graph).unwrap();
+        assert_matches_dijkstra(&graph, &ch);
+        assert_eq!(ch.shortest_path(0, 6), dijkstra_list(0, 6, graph));
+    }
+
+    #[test]
+    fn test_contraction_hierarchy_generated() {
+        let rng = &mut Rng::new(99);
+        let (geometric, _) = random_geometric(rng, 80, 0.2, 50);
+
+        for graph in [gnp(rng, 60, 0.05, true, 30), grid(rng, 7, 9, 10), geometric] {
+            let ch = ContractionHierarchy::new(&graph).unwrap();
+            assert_matches_dijkstra(&graph, &ch);
+
+            // shortcuts only ever point upwards in the order
+            for v in 0..ch.vertex_count() {
+                assert!(ch.up.edges(v).all(|(to, _)| ch.rank[to] > ch.rank[v]));
+                assert!(ch.down.edges(v).all(|(to, _)| ch.rank[to] > ch.rank[v]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_contraction_hierarchy_on_disk() {
+        let graph = grid(&mut Rng::new(4), 5, 5, 9);
+        let ch = ContractionHierarchy::new(&graph).unwrap();
+        let path = std::env::temp_dir().join(format!("ch_{}.bin", std::process::id()));
+
+        ch.save(&path).unwrap();
+        let loaded = ContractionHierarchy::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded, ch);
+        assert_eq!(loaded.query(0, 24), ch.query(0, 24));
+
+        let mut bytes = vec![];
+        ch.write_to(&mut bytes).unwrap();
+        let read = |bytes: &[u8]| ContractionHierarchy::read_from(&mut Cursor::new(bytes));
+        assert!(matches!(
+            read(&bytes[..bytes.len() - 2]),
+            Err(Error::InvalidFormat(_))
+        ));
+        assert!(matches!(read(&bytes[..10]), Err(Error::Io(_))));
+
+        let mut huge = bytes[..16].to_vec();
+        huge[8..16].copy_from_slice(&u64::from(u32::MAX).to_le_bytes());
+        assert!(matches!(read(&huge), Err(Error::InvalidFormat(_))));
+        huge[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(read(&huge), Err(Error::InvalidFormat(_))));
+
+        // two vertices with the same rank
+        let mut corrupt = bytes;
+        corrupt.copy_within(20..24, 16);
+        assert!(matches!(read(&corrupt), Err(Error::InvalidFormat(_))));
+
+        // a shortcut whose middle is its own tail would unpack forever
+        let v = (0..ch.vertex_count())
+            .find(|&v| ch.up.edges(v).next().is_some())
+            .unwrap();
+        let mut looping = ch.clone();
+        looping.up_middles[looping.up.offsets[v] as usize] = v as u32;
+        let mut bytes = vec![];
+        looping.write_to(&mut bytes).unwrap();
+        assert!(matches!(read(&bytes), Err(Error::InvalidFormat(_))));
+
+        // the same edges under the reversed order all point downwards
+        let mut reversed = ch;
+        let top = reversed.vertex_count() as u32 - 1;
+        reversed.rank.iter_mut().for_each(|r| *r = top - *r);
+        let mut bytes = vec![];
+        reversed.write_to(&mut bytes).unwrap();
+        assert!(matches!(read(&bytes), Err(Error::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_contraction_hierarchy_heavy_weights() {
+        // 1 -> 0 -> 2, and vertex 0 wins the tie to be contracted first
+        let heavy = |weight| WeightedAdjacencyList {
+            list: vec![
+                vec![GraphEdge { to: 2, weight }],
+                vec![GraphEdge { to: 0, weight }],
+                vec![],
+            ],
+        };
+        assert!(matches!(
+            ContractionHierarchy::new(&heavy(5_000_000_000)),
+            Err(Error::TooLarge("weight"))
+        ));
+        // each weight fits, but the shortcut 1 -> 2 adds both up
+        assert!(matches!(
+            ContractionHierarchy::new(&heavy(u32::MAX as usize)),
+            Err(Error::TooLarge("weight"))
+        ));
+
+        let ch = ContractionHierarchy::new(&heavy(u32::MAX as usize / 2)).unwrap();
+        assert_eq!(
+            ch.query(1, 2).unwrap(),
+            (u32::MAX as usize - 1, vec![1, 0, 2])
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+pub use crate::io_error::Error;
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    adjacency_matrix::WeightedAdjacencyMatrix,
+};
+
+const MAGIC: &[u8; 4] = b"CSRG";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsrGraph {
+    // edges of v are targets[offsets[v]..offsets[v + 1]]
+    pub offsets: Vec<u32>,
+    pub targets: Vec<u32>,
+    pub weights: Vec<u32>,
+}
+
+fn to_u32(value: usize, what: &'static str) -> Result<u32, Error> {
+    u32::try_from(value).map_err(|_| Error::TooLarge(what))
+}
+
+// the arrays go to disk as raw little endian u32s, so on little endian hosts
+// reading and writing is a single copy with no per element work
+pub(crate) fn write_u32s<W: Write>(writer: &mut W, values: &[u32]) -> io::Result<()> {
+    if cfg!(target_endian = "little") {
+        // any u32 is four valid bytes, and u8 has no alignment to break
+        let bytes =
+            unsafe { std::slice::from_raw_parts(values.as_ptr() as *const u8, values.len() * 4) };
+        writer.write_all(bytes)
+    } else {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        writer.write_all(&bytes)
+    }
+}
+
+// callers check `len` against `ensure_remaining` first, so a corrupt header
+// cannot make this allocate more than the input holds
+pub(crate) fn read_u32s<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u32>> {
+    let mut values = vec![0u32; len];
+    // as above, and every four bytes read in are a valid u32
+    let bytes = unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr() as *mut u8, len * 4) };
+    reader.read_exact(bytes)?;
+
+    if cfg!(target_endian = "big") {
+        for value in values.iter_mut() {
+            *value = u32::from_le(*value);
+        }
+    }
+
+    Ok(values)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+// a count from a file header; anything past u32 could not be indexed by the
+// arrays anyway
+pub(crate) fn read_count<R: Read>(reader: &mut R, what: &'static str) -> Result<usize, Error> {
+    usize::try_from(read_u64(reader)?)
+        .ok()
+        .filter(|&count| count <= u32::MAX as usize)
+        .ok_or(Error::InvalidFormat(what))
+}
+
+// fails unless at least `len` more u32s are left between the reader's
+// position and the end of its input
+pub(crate) fn ensure_remaining<R: Seek>(reader: &mut R, len: u64) -> Result<(), Error> {
+    let position = reader.stream_position()?;
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(position))?;
+
+    match end.saturating_sub(position) / 4 >= len {
+        true => Ok(()),
+        false => Err(Error::InvalidFormat(
+            "header counts more than the input holds",
+        )),
+    }
+}
+
+impl CsrGraph {
+    fn from_rows<I, E>(rows: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = E>,
+        E: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut offsets = vec![0];
+        let mut targets = vec![];
+        let mut weights = vec![];
+
+        for row in rows {
+            for (to, weight) in row {
+                targets.push(to_u32(to, "vertex id")?);
+                weights.push(to_u32(weight, "weight")?);
+            }
+            offsets.push(to_u32(targets.len(), "edge count")?);
+        }
+
+        Ok(Self {
+            offsets,
+            targets,
+            weights,
+        })
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn edges(&self, vertex: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = self.offsets[vertex] as usize..self.offsets[vertex + 1] as usize;
+        self.targets[range.clone()]
+            .iter()
+            .zip(self.weights[range].iter())
+            .map(|(&to, &weight)| (to as usize, weight as usize))
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.vertex_count() as u64).to_le_bytes())?;
+        writer.write_all(&(self.edge_count() as u64).to_le_bytes())?;
+        write_u32s(writer, &self.offsets)?;
+        write_u32s(writer, &self.targets)?;
+        write_u32s(writer, &self.weights)?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> Result<Self, Error> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::InvalidFormat("not a csr graph file"));
+        }
+
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        if u32::from_le_bytes(version) != VERSION {
+            return Err(Error::InvalidFormat("unsupported version"));
+        }
+
+        let vertices = read_count(reader, "vertex count too large")?;
+        let edges = read_count(reader, "edge count too large")?;
+        ensure_remaining(reader, vertices as u64 + 1 + 2 * edges as u64)?;
+
+        let graph = Self {
+            offsets: read_u32s(reader, vertices + 1)?,
+            targets: read_u32s(reader, edges)?,
+            weights: read_u32s(reader, edges)?,
+        };
+
+        // a bad file must not turn into out of bounds slicing later on
+        if graph.offsets[0] != 0
+            || graph.offsets[vertices] as usize != edges
+            || graph.offsets.windows(2).any(|w| w[0] > w[1])
+        {
+            return Err(Error::InvalidFormat("offsets are not a valid prefix sum"));
+        }
+        if graph.targets.iter().any(|&to| to as usize >= vertices) {
+            return Err(Error::InvalidFormat("edge target out of range"));
+        }
+
+        Ok(graph)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::read_from(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+impl TryFrom<&WeightedAdjacencyList> for CsrGraph {
+    type Error = Error;
+
+    fn try_from(graph: &WeightedAdjacencyList) -> Result<Self, Error> {
+        Self::from_rows(
+            graph
+                .list
+                .iter()
+                .map(|edges| edges.iter().map(|edge| (edge.to, edge.weight))),
+        )
+    }
+}
+
+impl TryFrom<&WeightedAdjacencyMatrix> for CsrGraph {
+    type Error = Error;
+
+    fn try_from(graph: &WeightedAdjacencyMatrix) -> Result<Self, Error> {
+        Self::from_rows(graph.matrix.iter().map(|row| {
+            row.iter()
+                .enumerate()
+                .filter_map(|(to, weight)| weight.map(|weight| (to, weight)))
+        }))
+    }
+}
+
+impl From<&CsrGraph> for WeightedAdjacencyList {
+    fn from(graph: &CsrGraph) -> Self {
+        WeightedAdjacencyList {
+            list: (0..graph.vertex_count())
+                .map(|v| {
+                    graph
+                        .edges(v)
+                        .map(|(to, weight)| GraphEdge { to, weight })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_csr_from_list() {
+        let list = crate::tests::graph::directed_list();
+        let csr = CsrGraph::try_from(&list).unwrap();
+
+        assert_eq!(csr.vertex_count(), 7);
+        assert_eq!(csr.edge_count(), 10);
+        assert_eq!(csr.offsets, vec![0, 2, 3, 4, 4, 7, 9, 10]);
+        assert_eq!(
+            csr.edges(4).collect::<Vec<_>>(),
+            vec![(1, 1), (3, 5), (5, 2)]
+        );
+        assert_eq!(csr.edges(3).count(), 0);
+
+        let back = WeightedAdjacencyList::from(&csr);
+        assert_eq!(CsrGraph::try_from(&back).unwrap(), csr);
+        assert_eq!(CsrGraph::default().vertex_count(), 0);
+    }
+
+    #[test]
+    fn test_csr_from_matrix() {
+        let matrix = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(1), Some(2)],
+                vec![None, None, None],
+                vec![Some(4), Some(3), None],
+            ],
+        };
+        let csr = CsrGraph::try_from(&matrix).unwrap();
+
+        assert_eq!(csr.offsets, vec![0, 2, 2, 4]);
+        assert_eq!(csr.targets, vec![1, 2, 0, 1]);
+        assert_eq!(csr.weights, vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_csr_too_large() {
+        let heavy = WeightedAdjacencyList {
+            list: vec![vec![GraphEdge {
+                to: 0,
+                weight: 5_000_000_000,
+            }]],
+        };
+        assert!(matches!(
+            CsrGraph::try_from(&heavy),
+            Err(Error::TooLarge("weight"))
+        ));
+
+        let far = WeightedAdjacencyMatrix {
+            matrix: vec![vec![Some(1 << 32)]],
+        };
+        assert!(matches!(
+            CsrGraph::try_from(&far),
+            Err(Error::TooLarge("weight"))
+        ));
+    }
+
+    #[test]
+    fn test_csr_on_disk() {
+        let csr = CsrGraph::try_from(&crate::tests::graph::undirected_list()).unwrap();
+        let path = std::env::temp_dir().join(format!("csr_graph_{}.bin", std::process::id()));
+
+        csr.save(&path).unwrap();
+        let loaded = CsrGraph::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), csr);
+
+        let mut bytes = vec![];
+        csr.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 24 + 4 * (8 + 2 * 19));
+        let read = |bytes: &[u8]| CsrGraph::read_from(&mut Cursor::new(bytes));
+        assert_eq!(read(&bytes).unwrap(), csr);
+
+        assert!(matches!(
+            read(&bytes[..bytes.len() - 1]),
+            Err(Error::InvalidFormat(_))
+        ));
+        assert!(matches!(read(&bytes[..2]), Err(Error::Io(_))));
+
+        // a header promising the most a u32 can count, with nothing after it
+        let mut huge = bytes[..24].to_vec();
+        huge[8..24].copy_from_slice(&[[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]; 2].concat());
+        assert!(matches!(read(&huge), Err(Error::InvalidFormat(_))));
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert!(matches!(read(&corrupt), Err(Error::InvalidFormat(_))));
+
+        // first target is the u32 after the header and the 8 offsets
+        let mut corrupt = bytes;
+        corrupt[24 + 32] = 42;
+        assert!(matches!(read(&corrupt), Err(Error::InvalidFormat(_))));
+    }
+}
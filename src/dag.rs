@@ -0,0 +1,364 @@
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    queue::Queue,
+    stack::Stack,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Cycle(Vec<usize>),
+}
+
+#[derive(Clone, Debug)]
+pub struct DagPaths {
+    pub dists: Vec<Option<usize>>,
+    pub prev: Vec<Option<usize>>,
+}
+
+impl DagPaths {
+    pub fn path_to(&self, destination: usize) -> Vec<usize> {
+        if self.dists[destination].is_none() {
+            return vec![];
+        }
+
+        let mut out = vec![destination];
+        let mut curr = destination;
+        while let Some(prev) = self.prev[curr] {
+            out.push(prev);
+            curr = prev;
+        }
+
+        out.into_iter().rev().collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Colour {
+    White,
+    Grey,
+    Black,
+}
+
+pub fn find_cycle(graph: &WeightedAdjacencyList) -> Option<Vec<usize>> {
+    let mut colour = vec![Colour::White; graph.list.len()];
+    let mut parent = vec![None; graph.list.len()];
+
+    for root in 0..graph.list.len() {
+        if colour[root] != Colour::White {
+            continue;
+        }
+
+        let mut stack = Stack::new();
+        stack.push((root, 0));
+        colour[root] = Colour::Grey;
+
+        while let Some(top) = stack.peek_mut() {
+            let (curr, i) = *top;
+
+            if i == graph.list[curr].len() {
+                colour[curr] = Colour::Black;
+                stack.pop();
+                continue;
+            }
+
+            top.1 += 1;
+            let next = graph.list[curr][i].to;
+
+            match colour[next] {
+                Colour::White => {
+                    colour[next] = Colour::Grey;
+                    parent[next] = Some(curr);
+                    stack.push((next, 0));
+                }
+                // a grey vertex is still on the stack, so walking the parents
+                // back from curr reaches it
+                Colour::Grey => {
+                    let mut cycle = vec![curr];
+                    let mut v = curr;
+                    while v != next {
+                        v = parent[v].unwrap();
+                        cycle.push(v);
+                    }
+                    return Some(cycle.into_iter().rev().collect());
+                }
+                Colour::Black => {}
+            }
+        }
+    }
+
+    None
+}
+
+pub fn kahn(graph: &WeightedAdjacencyList) -> Result<Vec<usize>, Error> {
+    let mut in_degree = vec![0; graph.list.len()];
+    for edges in graph.list.iter() {
+        for edge in edges.iter() {
+            in_degree[edge.to] += 1;
+        }
+    }
+
+    let mut queue = Queue::new();
+    for (v, degree) in in_degree.iter().enumerate() {
+        if *degree == 0 {
+            queue.enqueue(v);
+        }
+    }
+
+    let mut out = vec![];
+    while let Some(curr) = queue.deque() {
+        out.push(curr);
+
+        for edge in graph.list[curr].iter() {
+            in_degree[edge.to] -= 1;
+            if in_degree[edge.to] == 0 {
+                queue.enqueue(edge.to);
+            }
+        }
+    }
+
+    if out.len() < graph.list.len() {
+        return Err(Error::Cycle(find_cycle(graph).unwrap()));
+    }
+
+    Ok(out)
+}
+
+pub fn topological_sort(graph: &WeightedAdjacencyList) -> Result<Vec<usize>, Error> {
+    if let Some(cycle) = find_cycle(graph) {
+        return Err(Error::Cycle(cycle));
+    }
+
+    let mut seen = vec![false; graph.list.len()];
+    let mut out = vec![];
+
+    for root in 0..graph.list.len() {
+        if seen[root] {
+            continue;
+        }
+
+        let mut stack = Stack::new();
+        stack.push((root, 0));
+        seen[root] = true;
+
+        while let Some(top) = stack.peek_mut() {
+            let (curr, i) = *top;
+
+            if i == graph.list[curr].len() {
+                // post order
+                out.push(curr);
+                stack.pop();
+                continue;
+            }
+
+            top.1 += 1;
+            let next = graph.list[curr][i].to;
+            if !seen[next] {
+                seen[next] = true;
+                stack.push((next, 0));
+            }
+        }
+    }
+
+    Ok(out.into_iter().rev().collect())
+}
+
+pub fn shortest_paths(graph: &WeightedAdjacencyList, source: usize) -> Result<DagPaths, Error> {
+    let order = topological_sort(graph)?;
+    let mut dists = vec![None; graph.list.len()];
+    let mut prev = vec![None; graph.list.len()];
+    dists[source] = Some(0);
+
+    for curr in order {
+        let Some(dist) = dists[curr] else {
+            continue;
+        };
+
+        for edge in graph.list[curr].iter() {
+            let new_dist = dist + edge.weight;
+            if dists[edge.to].is_none_or(|d| new_dist < d) {
+                dists[edge.to] = Some(new_dist);
+                prev[edge.to] = Some(curr);
+            }
+        }
+    }
+
+    Ok(DagPaths { dists, prev })
+}
+
+pub fn longest_path(graph: &WeightedAdjacencyList) -> Result<(usize, Vec<usize>), Error> {
+    let order = topological_sort(graph)?;
+    if order.is_empty() {
+        return Ok((0, vec![]));
+    }
+
+    // every vertex may start a path, so they all begin at distance zero
+    let mut dists = vec![0; graph.list.len()];
+    let mut prev = vec![None; graph.list.len()];
+
+    for curr in order {
+        for edge in graph.list[curr].iter() {
+            let new_dist = dists[curr] + edge.weight;
+            if prev[edge.to].is_none() || new_dist > dists[edge.to] {
+                dists[edge.to] = new_dist;
+                prev[edge.to] = Some(curr);
+            }
+        }
+    }
+
+    let mut end = 0;
+    for v in 0..dists.len() {
+        if dists[v] > dists[end] {
+            end = v;
+        }
+    }
+
+    let paths = DagPaths {
+        dists: dists.iter().map(|&d| Some(d)).collect(),
+        prev,
+    };
+
+    Ok((dists[end], paths.path_to(end)))
+}
+
+pub fn transitive_closure(graph: &WeightedAdjacencyList) -> Result<Vec<Vec<bool>>, Error> {
+    let order = topological_sort(graph)?;
+    let mut reach = vec![vec![false; graph.list.len()]; graph.list.len()];
+
+    // successors come later in the order, so walking it backwards means every
+    // successor row is already complete
+    for &curr in order.iter().rev() {
+        for edge in graph.list[curr].iter() {
+            let successor = reach[edge.to].clone();
+            reach[curr][edge.to] = true;
+            for (r, s) in reach[curr].iter_mut().zip(successor) {
+                *r |= s;
+            }
+        }
+    }
+
+    Ok(reach)
+}
+
+pub fn transitive_reduction(graph: &WeightedAdjacencyList) -> Result<WeightedAdjacencyList, Error> {
+    let reach = transitive_closure(graph)?;
+    let mut list: Vec<Vec<GraphEdge>> = vec![vec![]; graph.list.len()];
+
+    for (curr, edges) in graph.list.iter().enumerate() {
+        for edge in edges.iter() {
+            let redundant = edges
+                .iter()
+                .any(|other| other.to != edge.to && reach[other.to][edge.to]);
+            let duplicate = list[curr].iter().any(|kept| kept.to == edge.to);
+
+            if !redundant && !duplicate {
+                list[curr].push(edge.clone());
+            }
+        }
+    }
+
+    Ok(WeightedAdjacencyList { list })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_topological(graph: &WeightedAdjacencyList, order: &[usize]) -> bool {
+        let mut position = vec![0; order.len()];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+
+        graph
+            .list
+            .iter()
+            .enumerate()
+            .all(|(from, edges)| edges.iter().all(|edge| position[from] < position[edge.to]))
+    }
+
+    fn dag() -> WeightedAdjacencyList {
+        // (0) --> (1) --> (3) --> (4)
+        //   \      |      ^
+        //    \     v     /
+        //     --> (2) ---
+        WeightedAdjacencyList {
+            list: vec![
+                vec![
+                    GraphEdge { to: 1, weight: 2 },
+                    GraphEdge { to: 2, weight: 9 },
+                ],
+                vec![
+                    GraphEdge { to: 2, weight: 3 },
+                    GraphEdge { to: 3, weight: 1 },
+                ],
+                vec![GraphEdge { to: 3, weight: 4 }],
+                vec![GraphEdge { to: 4, weight: 1 }],
+                vec![],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = dag();
+        assert_eq!(kahn(&graph), Ok(vec![0, 1, 2, 3, 4]));
+        assert_eq!(topological_sort(&graph), Ok(vec![0, 1, 2, 3, 4]));
+        assert_eq!(find_cycle(&graph), None);
+
+        let graph = crate::tests::graph::directed_list();
+        assert_eq!(find_cycle(&graph), Some(vec![1, 4]));
+        assert_eq!(kahn(&graph), Err(Error::Cycle(vec![1, 4])));
+        assert!(topological_sort(&graph).is_err());
+
+        let mut graph = dag();
+        graph.list[1].clear();
+        graph.list[1].push(GraphEdge { to: 3, weight: 1 });
+        let order = kahn(&graph).unwrap();
+        assert!(is_topological(&graph, &order));
+        let order = topological_sort(&graph).unwrap();
+        assert!(is_topological(&graph, &order));
+    }
+
+    #[test]
+    fn test_dag_paths() {
+        let graph = dag();
+
+        let paths = shortest_paths(&graph, 0).unwrap();
+        assert_eq!(
+            paths.dists,
+            vec![Some(0), Some(2), Some(5), Some(3), Some(4)]
+        );
+        assert_eq!(paths.path_to(4), vec![0, 1, 3, 4]);
+
+        let paths = shortest_paths(&graph, 2).unwrap();
+        assert_eq!(paths.dists, vec![None, None, Some(0), Some(4), Some(5)]);
+        assert_eq!(paths.path_to(0), vec![]);
+
+        assert_eq!(longest_path(&graph), Ok((14, vec![0, 2, 3, 4])));
+    }
+
+    #[test]
+    fn test_transitive() {
+        let graph = dag();
+
+        let closure = transitive_closure(&graph).unwrap();
+        assert_eq!(
+            closure,
+            vec![
+                vec![false, true, true, true, true],
+                vec![false, false, true, true, true],
+                vec![false, false, false, true, true],
+                vec![false, false, false, false, true],
+                vec![false, false, false, false, false],
+            ]
+        );
+
+        let reduction = transitive_reduction(&graph).unwrap();
+        let edges: Vec<Vec<usize>> = reduction
+            .list
+            .iter()
+            .map(|edges| edges.iter().map(|edge| edge.to).collect())
+            .collect();
+        assert_eq!(edges, vec![vec![1], vec![2], vec![3], vec![4], vec![]]);
+    }
+}
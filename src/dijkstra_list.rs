@@ -28,18 +28,18 @@ fn get_lowest_unvisited(seen: &mut Vec<bool>, dists: &mut Vec<usize>) -> Option<
     lowest_index
 }
 
-pub fn dijkstra_list(
-    source: usize,
-    destination: usize,
-    graph: WeightedAdjacencyList,
-) -> Vec<usize> {
-    if destination >= graph.list.len() {
-        return vec![];
-    }
+#[derive(Clone, Debug)]
+pub struct DijkstraTree {
+    pub prev: Vec<Option<usize>>,
+    pub dists: Vec<usize>,
+    pub order: Vec<usize>,
+}
 
+pub fn dijkstra_tree(source: usize, graph: &WeightedAdjacencyList) -> DijkstraTree {
     let mut seen = vec![false; graph.list.len()];
     let mut prev = vec![None; graph.list.len()];
     let mut dists = vec![usize::MAX; graph.list.len()];
+    let mut order = vec![];
 
     dists[source] = 0;
 
@@ -47,6 +47,7 @@ pub fn dijkstra_list(
         let curr = get_lowest_unvisited(&mut seen, &mut dists).unwrap();
 
         seen[curr] = true;
+        order.push(curr);
 
         for edge in graph.list[curr].iter() {
             if seen[edge.to] {
@@ -61,6 +62,20 @@ pub fn dijkstra_list(
         }
     }
 
+    DijkstraTree { prev, dists, order }
+}
+
+pub fn dijkstra_list(
+    source: usize,
+    destination: usize,
+    graph: WeightedAdjacencyList,
+) -> Vec<usize> {
+    if destination >= graph.list.len() {
+        return vec![];
+    }
+
+    let prev = dijkstra_tree(source, &graph).prev;
+
     if matches!(prev[destination], None) {
         return vec![];
     }
@@ -79,55 +94,30 @@ pub fn dijkstra_list(
 
 #[cfg(test)]
 mod tests {
-    use crate::adjacency_list::GraphEdge;
-
     use super::*;
+    use crate::tests::graph::undirected_list;
 
     #[test]
     fn dijkstra_list_test() {
-        //      (1) --- (4) ---- (5)
-        //    /  |       |       /|
-        // (0)   | ------|------- |
-        //    \  |/      |        |
-        //      (2) --- (3) ---- (6)
-        let list = WeightedAdjacencyList {
-            list: vec![
-                vec![
-                    GraphEdge { to: 1, weight: 3 },
-                    GraphEdge { to: 2, weight: 1 },
-                ],
-                vec![
-                    GraphEdge { to: 0, weight: 3 },
-                    GraphEdge { to: 2, weight: 4 },
-                    GraphEdge { to: 4, weight: 1 },
-                ],
-                vec![
-                    GraphEdge { to: 1, weight: 4 },
-                    GraphEdge { to: 3, weight: 7 },
-                    GraphEdge { to: 0, weight: 1 },
-                ],
-                vec![
-                    GraphEdge { to: 2, weight: 7 },
-                    GraphEdge { to: 4, weight: 5 },
-                    GraphEdge { to: 6, weight: 1 },
-                ],
-                vec![
-                    GraphEdge { to: 1, weight: 1 },
-                    GraphEdge { to: 3, weight: 5 },
-                    GraphEdge { to: 5, weight: 2 },
-                ],
-                vec![
-                    GraphEdge { to: 6, weight: 1 },
-                    GraphEdge { to: 4, weight: 2 },
-                    GraphEdge { to: 2, weight: 18 },
-                ],
-                vec![
-                    GraphEdge { to: 3, weight: 1 },
-                    GraphEdge { to: 5, weight: 1 },
-                ],
-            ],
-        };
-
-        assert_eq!(dijkstra_list(0, 6, list), vec![0, 1, 4, 5, 6])
+        let list = undirected_list();
+
+        assert_eq!(dijkstra_list(0, 6, list.clone()), vec![0, 1, 4, 5, 6]);
+
+        let tree = dijkstra_tree(0, &list);
+        assert_eq!(tree.dists, vec![0, 3, 1, 8, 4, 6, 7]);
+        assert_eq!(tree.order, vec![0, 2, 1, 4, 5, 6, 3]);
+    }
+
+    #[test]
+    fn test_dijkstra_generated() {
+        use crate::generators::{grid, Rng};
+
+        // with unit weights the distance across a grid is the manhattan distance
+        let graph = grid(&mut Rng::new(5), 6, 8, 1);
+        let tree = dijkstra_tree(0, &graph);
+        for v in 0..48 {
+            assert_eq!(tree.dists[v], v / 8 + v % 8);
+        }
+        assert_eq!(dijkstra_list(0, 47, graph).len(), 6 + 8 - 1);
     }
 }
@@ -0,0 +1,206 @@
+use std::{collections::HashSet, fmt::Write};
+
+use crate::{
+    adjacency_list::WeightedAdjacencyList,
+    adjacency_matrix::{bfs, bfs_tree, WeightedAdjacencyMatrix},
+    dijkstra_list::{dijkstra_list, dijkstra_tree},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions {
+    pub directed: bool,
+    pub path: Vec<usize>,
+    pub order: Vec<usize>,
+    pub dists: Vec<Option<usize>>,
+    pub tree: Vec<(usize, usize)>,
+}
+
+pub fn tree_edges(prev: &[Option<usize>]) -> Vec<(usize, usize)> {
+    prev.iter()
+        .enumerate()
+        .filter_map(|(to, from)| from.map(|from| (from, to)))
+        .collect()
+}
+
+fn contains_edge(edges: &[(usize, usize)], from: usize, to: usize, directed: bool) -> bool {
+    edges
+        .iter()
+        .any(|&(a, b)| (a, b) == (from, to) || (!directed && (b, a) == (from, to)))
+}
+
+fn render(vertices: usize, edges: &[(usize, usize, usize)], options: &DotOptions) -> String {
+    let (keyword, arrow) = if options.directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+    let path_edges: Vec<(usize, usize)> = options.path.windows(2).map(|w| (w[0], w[1])).collect();
+
+    let mut out = String::new();
+    writeln!(out, "{} {{", keyword).unwrap();
+
+    for v in 0..vertices {
+        let mut label = v.to_string();
+        if let Some(position) = options.order.iter().position(|&o| o == v) {
+            write!(label, "\\n#{}", position).unwrap();
+        }
+        if let Some(Some(dist)) = options.dists.get(v) {
+            write!(label, "\\nd={}", dist).unwrap();
+        }
+
+        let mut attrs = vec![format!("label=\"{}\"", label)];
+        if options.path.contains(&v) {
+            attrs.push("style=filled".to_string());
+            attrs.push("fillcolor=salmon".to_string());
+        }
+        writeln!(out, "    {} [{}];", v, attrs.join(", ")).unwrap();
+    }
+
+    let mut drawn = HashSet::new();
+    for &(from, to, weight) in edges {
+        if !options.directed && !drawn.insert((from.min(to), from.max(to))) {
+            continue;
+        }
+
+        let mut attrs = vec![format!("label=\"{}\"", weight)];
+        if contains_edge(&path_edges, from, to, options.directed) {
+            attrs.push("color=red".to_string());
+            attrs.push("penwidth=2".to_string());
+        } else if contains_edge(&options.tree, from, to, options.directed) {
+            attrs.push("color=blue".to_string());
+            attrs.push("style=bold".to_string());
+        }
+        writeln!(out, "    {} {} {} [{}];", from, arrow, to, attrs.join(", ")).unwrap();
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+pub fn list_to_dot(graph: &WeightedAdjacencyList, options: &DotOptions) -> String {
+    let edges: Vec<(usize, usize, usize)> = graph
+        .list
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge.to, edge.weight)))
+        .collect();
+
+    render(graph.list.len(), &edges, options)
+}
+
+pub fn matrix_to_dot(graph: &WeightedAdjacencyMatrix, options: &DotOptions) -> String {
+    let edges: Vec<(usize, usize, usize)> = graph
+        .matrix
+        .iter()
+        .enumerate()
+        .flat_map(|(from, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(to, weight)| weight.map(|weight| (from, to, weight)))
+        })
+        .collect();
+
+    render(graph.matrix.len(), &edges, options)
+}
+
+pub fn bfs_to_dot(
+    graph: &WeightedAdjacencyMatrix,
+    source: usize,
+    needle: usize,
+    directed: bool,
+) -> String {
+    let tree = bfs_tree(graph, source);
+    let options = DotOptions {
+        directed,
+        path: bfs(graph.clone(), source, needle),
+        order: tree.order,
+        dists: tree.depth,
+        tree: tree_edges(&tree.prev),
+    };
+
+    matrix_to_dot(graph, &options)
+}
+
+pub fn dijkstra_to_dot(
+    graph: &WeightedAdjacencyList,
+    source: usize,
+    destination: usize,
+    directed: bool,
+) -> String {
+    let tree = dijkstra_tree(source, graph);
+    let options = DotOptions {
+        directed,
+        path: dijkstra_list(source, destination, graph.clone()),
+        order: tree.order,
+        dists: tree
+            .dists
+            .iter()
+            .map(|&d| if d == usize::MAX { None } else { Some(d) })
+            .collect(),
+        tree: tree_edges(&tree.prev),
+    };
+
+    list_to_dot(graph, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    #[test]
+    fn test_list_to_dot() {
+        // (0) --> (1) --> (2)
+        //   \_____________^
+        let graph = WeightedAdjacencyList {
+            list: vec![
+                vec![
+                    GraphEdge { to: 1, weight: 1 },
+                    GraphEdge { to: 2, weight: 5 },
+                ],
+                vec![GraphEdge { to: 2, weight: 1 }],
+                vec![],
+            ],
+        };
+
+        assert_eq!(
+            dijkstra_to_dot(&graph, 0, 2, true),
+            "digraph {\n\
+            \x20   0 [label=\"0\\n#0\\nd=0\", style=filled, fillcolor=salmon];\n\
+            \x20   1 [label=\"1\\n#1\\nd=1\", style=filled, fillcolor=salmon];\n\
+            \x20   2 [label=\"2\\n#2\\nd=2\", style=filled, fillcolor=salmon];\n\
+            \x20   0 -> 1 [label=\"1\", color=red, penwidth=2];\n\
+            \x20   0 -> 2 [label=\"5\"];\n\
+            \x20   1 -> 2 [label=\"1\", color=red, penwidth=2];\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn test_matrix_to_dot() {
+        // (0) --- (1)    (3)
+        //   \     /
+        //     (2)
+        let graph = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(1), Some(2), None],
+                vec![Some(1), None, Some(3), None],
+                vec![Some(2), Some(3), None, None],
+                vec![None, None, None, None],
+            ],
+        };
+
+        assert_eq!(
+            bfs_to_dot(&graph, 0, 2, false),
+            "graph {\n\
+            \x20   0 [label=\"0\\n#0\\nd=0\", style=filled, fillcolor=salmon];\n\
+            \x20   1 [label=\"1\\n#1\\nd=1\"];\n\
+            \x20   2 [label=\"2\\n#2\\nd=1\", style=filled, fillcolor=salmon];\n\
+            \x20   3 [label=\"3\"];\n\
+            \x20   0 -- 1 [label=\"1\", color=blue, style=bold];\n\
+            \x20   0 -- 2 [label=\"2\", color=red, penwidth=2];\n\
+            \x20   1 -- 2 [label=\"3\"];\n\
+            }\n"
+        );
+    }
+}
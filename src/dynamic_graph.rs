@@ -0,0 +1,325 @@
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    dijkstra_list::dijkstra_tree,
+    min_heap::MinHeap,
+};
+
+// one edge per ordered pair, kept next to an incoming copy so repairs can look
+// at predecessors without scanning the whole graph
+#[derive(Clone, Debug)]
+pub struct DynamicGraph {
+    graph: WeightedAdjacencyList,
+    incoming: Vec<Vec<GraphEdge>>,
+}
+
+impl DynamicGraph {
+    pub fn new(graph: &WeightedAdjacencyList) -> Self {
+        let mut out = DynamicGraph {
+            graph: WeightedAdjacencyList {
+                list: vec![vec![]; graph.list.len()],
+            },
+            incoming: vec![vec![]; graph.list.len()],
+        };
+
+        // parallel edges collapse into the cheapest one
+        for (from, edges) in graph.list.iter().enumerate() {
+            for edge in edges.iter() {
+                let weight = out
+                    .weight(from, edge.to)
+                    .map_or(edge.weight, |weight| weight.min(edge.weight));
+                out.insert_edge(from, edge.to, weight);
+            }
+        }
+
+        out
+    }
+
+    pub fn graph(&self) -> &WeightedAdjacencyList {
+        &self.graph
+    }
+
+    pub fn incoming(&self, vertex: usize) -> &[GraphEdge] {
+        &self.incoming[vertex]
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.graph.list.len()
+    }
+
+    pub fn add_vertex(&mut self) -> usize {
+        self.graph.list.push(vec![]);
+        self.incoming.push(vec![]);
+        self.graph.list.len() - 1
+    }
+
+    pub fn weight(&self, from: usize, to: usize) -> Option<usize> {
+        self.graph.list[from]
+            .iter()
+            .find(|edge| edge.to == to)
+            .map(|edge| edge.weight)
+    }
+
+    // adds the edge or overwrites its weight, returning the old one
+    pub fn insert_edge(&mut self, from: usize, to: usize, weight: usize) -> Option<usize> {
+        let old = self.remove_edge(from, to);
+        self.graph.list[from].push(GraphEdge { to, weight });
+        self.incoming[to].push(GraphEdge { to: from, weight });
+        old
+    }
+
+    pub fn set_weight(&mut self, from: usize, to: usize, weight: usize) -> Option<usize> {
+        self.weight(from, to)?;
+        self.insert_edge(from, to, weight)
+    }
+
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> Option<usize> {
+        let position = self.graph.list[from]
+            .iter()
+            .position(|edge| edge.to == to)?;
+        let old = self.graph.list[from].swap_remove(position).weight;
+
+        let position = self.incoming[to]
+            .iter()
+            .position(|edge| edge.to == from)
+            .unwrap();
+        self.incoming[to].swap_remove(position);
+
+        Some(old)
+    }
+}
+
+// single source distances kept up to date across edge changes, in the spirit
+// of Ramalingam-Reps: decreases push a Dijkstra wave out of the improved
+// vertex, increases on tree edges reset the subtree below and rebuild it from
+// its unaffected in-neighbours
+#[derive(Clone, Debug)]
+pub struct IncrementalSssp {
+    pub source: usize,
+    // usize::MAX marks an unreachable vertex, as in `DijkstraTree`
+    pub dists: Vec<usize>,
+    pub prev: Vec<Option<usize>>,
+}
+
+impl IncrementalSssp {
+    pub fn new(graph: &DynamicGraph, source: usize) -> Self {
+        let tree = dijkstra_tree(source, graph.graph());
+        IncrementalSssp {
+            source,
+            dists: tree.dists,
+            prev: tree.prev,
+        }
+    }
+
+    pub fn path_to(&self, vertex: usize) -> Option<Vec<usize>> {
+        if self.dists[vertex] == usize::MAX {
+            return None;
+        }
+
+        let mut out = vec![vertex];
+        let mut curr = vertex;
+        while let Some(prev) = self.prev[curr] {
+            out.push(prev);
+            curr = prev;
+        }
+
+        Some(out.into_iter().rev().collect())
+    }
+
+    // call after changing the edge from -> to, with the weight it had before
+    // (None if it did not exist)
+    pub fn update(&mut self, graph: &DynamicGraph, from: usize, to: usize, old: Option<usize>) {
+        self.dists.resize(graph.vertex_count(), usize::MAX);
+        self.prev.resize(graph.vertex_count(), None);
+
+        let new = graph.weight(from, to);
+        match (old, new) {
+            (_, Some(new)) if old.is_none_or(|old| new < old) => {
+                self.decrease(graph, from, to, new)
+            }
+            (Some(_), _) if self.prev[to] == Some(from) => self.increase(graph, to),
+            _ => {}
+        }
+    }
+
+    fn decrease(&mut self, graph: &DynamicGraph, from: usize, to: usize, weight: usize) {
+        if self.dists[from] == usize::MAX || self.dists[from] + weight >= self.dists[to] {
+            return;
+        }
+
+        self.dists[to] = self.dists[from] + weight;
+        self.prev[to] = Some(from);
+
+        let mut heap = MinHeap::new();
+        heap.insert((self.dists[to], to));
+        self.propagate(graph, &mut heap);
+    }
+
+    fn increase(&mut self, graph: &DynamicGraph, root: usize) {
+        // everything whose shortest path ran through root may have got longer
+        let mut affected = vec![false; graph.vertex_count()];
+        let mut stack = vec![root];
+        let mut subtree = vec![];
+        affected[root] = true;
+
+        while let Some(curr) = stack.pop() {
+            subtree.push(curr);
+            for edge in graph.graph().list[curr].iter() {
+                if !affected[edge.to] && self.prev[edge.to] == Some(curr) {
+                    affected[edge.to] = true;
+                    stack.push(edge.to);
+                }
+            }
+        }
+
+        for &v in subtree.iter() {
+            self.dists[v] = usize::MAX;
+            self.prev[v] = None;
+        }
+
+        let mut heap = MinHeap::new();
+        for &v in subtree.iter() {
+            for edge in graph.incoming(v) {
+                let dist = self.dists[edge.to];
+                if affected[edge.to] || dist == usize::MAX {
+                    continue;
+                }
+
+                if dist + edge.weight < self.dists[v] {
+                    self.dists[v] = dist + edge.weight;
+                    self.prev[v] = Some(edge.to);
+                }
+            }
+
+            if self.dists[v] != usize::MAX {
+                heap.insert((self.dists[v], v));
+            }
+        }
+
+        self.propagate(graph, &mut heap);
+    }
+
+    fn propagate(&mut self, graph: &DynamicGraph, heap: &mut MinHeap<(usize, usize)>) {
+        while let Some((dist, curr)) = heap.delete() {
+            if dist > self.dists[curr] {
+                continue;
+            }
+
+            for edge in graph.graph().list[curr].iter() {
+                let new_dist = dist + edge.weight;
+                if new_dist < self.dists[edge.to] {
+                    self.dists[edge.to] = new_dist;
+                    self.prev[edge.to] = Some(curr);
+                    heap.insert((new_dist, edge.to));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{gnp, grid, Rng};
+
+    fn assert_matches_dijkstra(graph: &DynamicGraph, sssp: &IncrementalSssp) {
+        let expected = dijkstra_tree(sssp.source, graph.graph());
+        assert_eq!(sssp.dists, expected.dists);
+
+        // ties may pick other predecessors, but each must be a real shortest step
+        for v in 0..graph.vertex_count() {
+            match sssp.prev[v] {
+                Some(prev) => assert_eq!(
+                    sssp.dists[prev] + graph.weight(prev, v).unwrap(),
+                    sssp.dists[v]
+                ),
+                None => assert!(v == sssp.source || sssp.dists[v] == usize::MAX),
+            }
+        }
+    }
+
+    #[test]
+    fn test_dynamic_graph() {
+        let mut list = crate::tests::graph::directed_list();
+        list.list[0].push(GraphEdge { to: 1, weight: 9 });
+        let mut graph = DynamicGraph::new(&list);
+
+        assert_eq!(graph.weight(0, 1), Some(3));
+        assert_eq!(graph.incoming(3).len(), 3);
+        assert_eq!(graph.set_weight(0, 1, 4), Some(3));
+        assert_eq!(graph.set_weight(3, 0, 4), None);
+        assert_eq!(graph.weight(3, 0), None);
+        assert_eq!(graph.insert_edge(3, 0, 2), None);
+        assert_eq!(graph.remove_edge(4, 3), Some(5));
+        assert_eq!(graph.remove_edge(4, 3), None);
+        assert_eq!(graph.incoming(3).len(), 2);
+
+        let v = graph.add_vertex();
+        graph.insert_edge(v, 0, 1);
+        assert_eq!(graph.incoming(0).len(), 2);
+    }
+
+    #[test]
+    fn test_incremental_sssp() {
+        let mut graph = DynamicGraph::new(&crate::tests::graph::directed_list());
+        let mut sssp = IncrementalSssp::new(&graph, 0);
+        assert_eq!(sssp.path_to(6), Some(vec![0, 1, 4, 5, 6]));
+
+        // cutting 4 -> 5 strands 5 and 6
+        let old = graph.remove_edge(4, 5);
+        sssp.update(&graph, 4, 5, old);
+        assert_eq!(sssp.path_to(6), None);
+        assert_eq!(sssp.dists[3], 8);
+        assert_matches_dijkstra(&graph, &sssp);
+
+        let old = graph.insert_edge(2, 6, 1);
+        sssp.update(&graph, 2, 6, old);
+        assert_eq!(sssp.path_to(3), Some(vec![0, 2, 6, 3]));
+        assert_matches_dijkstra(&graph, &sssp);
+
+        let old = graph.set_weight(0, 2, 10);
+        sssp.update(&graph, 0, 2, old);
+        assert_eq!(sssp.dists[3], 9);
+        assert_matches_dijkstra(&graph, &sssp);
+
+        let v = graph.add_vertex();
+        let old = graph.insert_edge(1, v, 2);
+        sssp.update(&graph, 1, v, old);
+        assert_eq!(sssp.dists[v], 5);
+    }
+
+    #[test]
+    fn test_incremental_sssp_random() {
+        let rng = &mut Rng::new(2024);
+
+        for list in [gnp(rng, 60, 0.06, true, 20), grid(rng, 8, 8, 9)] {
+            let mut graph = DynamicGraph::new(&list);
+            let mut sssp = IncrementalSssp::new(&graph, 0);
+            let len = graph.vertex_count();
+
+            for _ in 0..400 {
+                let from = rng.below(len);
+                let to = rng.below(len);
+                if from == to {
+                    continue;
+                }
+
+                let old = match rng.below(3) {
+                    0 => graph.insert_edge(from, to, rng.range(1, 20)),
+                    1 => graph.remove_edge(from, to),
+                    _ => match graph.graph().list[from].first().map(|edge| edge.to) {
+                        Some(to) => {
+                            let old = graph.set_weight(from, to, rng.range(1, 20));
+                            sssp.update(&graph, from, to, old);
+                            assert_matches_dijkstra(&graph, &sssp);
+                            continue;
+                        }
+                        None => continue,
+                    },
+                };
+
+                sssp.update(&graph, from, to, old);
+                assert_matches_dijkstra(&graph, &sssp);
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{adjacency_list::WeightedAdjacencyList, connectivity::connected_components};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    NoEdges,
+    // edges live in more than one connected component
+    Disconnected,
+    // directed: vertices whose in and out degrees differ more than allowed
+    Unbalanced(Vec<usize>),
+    // undirected: vertices of odd degree, too many of them for a path or circuit
+    OddDegree(Vec<usize>),
+}
+
+// undirected edges are counted as by `WeightedAdjacencyList::undirected_multi`:
+// listing an edge both ways does not double it, listing it twice one way does
+fn edges(graph: &WeightedAdjacencyList, directed: bool) -> Vec<(usize, usize)> {
+    if directed {
+        return graph
+            .list
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge.to)))
+            .collect();
+    }
+
+    graph
+        .undirected_multi()
+        .list
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| {
+            edges
+                .iter()
+                .filter(move |edge| from <= edge.to)
+                .map(move |edge| (from, edge.to))
+        })
+        .collect()
+}
+
+// checks the degree and connectivity conditions and returns where the walk
+// has to start
+pub fn check(graph: &WeightedAdjacencyList, directed: bool, circuit: bool) -> Result<usize, Error> {
+    let edges = edges(graph, directed);
+    if edges.is_empty() {
+        return Err(Error::NoEdges);
+    }
+
+    let components = connected_components(graph);
+    let component = components.ids[edges[0].0];
+    if edges
+        .iter()
+        .any(|&(from, _)| components.ids[from] != component)
+    {
+        return Err(Error::Disconnected);
+    }
+
+    let len = graph.list.len();
+    let mut out_degree = vec![0isize; len];
+    let mut in_degree = vec![0isize; len];
+    for &(from, to) in edges.iter() {
+        out_degree[from] += 1;
+        in_degree[to] += 1;
+    }
+
+    if directed {
+        let starts: Vec<usize> = (0..len)
+            .filter(|&v| out_degree[v] - in_degree[v] == 1)
+            .collect();
+        let ends: Vec<usize> = (0..len)
+            .filter(|&v| in_degree[v] - out_degree[v] == 1)
+            .collect();
+        let others: Vec<usize> = (0..len)
+            .filter(|&v| (out_degree[v] - in_degree[v]).abs() > 1)
+            .collect();
+
+        let balanced = starts.is_empty() && ends.is_empty();
+        let path = starts.len() == 1 && ends.len() == 1;
+        if !others.is_empty() || !(balanced || (path && !circuit)) {
+            let mut unbalanced: Vec<usize> = (0..len)
+                .filter(|&v| out_degree[v] != in_degree[v])
+                .collect();
+            unbalanced.sort();
+            return Err(Error::Unbalanced(unbalanced));
+        }
+
+        return Ok(starts.first().copied().unwrap_or(edges[0].0));
+    }
+
+    let odd: Vec<usize> = (0..len)
+        .filter(|&v| (out_degree[v] + in_degree[v]) % 2 == 1)
+        .collect();
+    match odd.len() {
+        0 => Ok(edges[0].0),
+        2 if !circuit => Ok(odd[0]),
+        _ => Err(Error::OddDegree(odd)),
+    }
+}
+
+fn hierholzer(graph: &WeightedAdjacencyList, directed: bool, start: usize) -> Vec<usize> {
+    let edges = edges(graph, directed);
+    let mut adjacent: Vec<Vec<(usize, usize)>> = vec![vec![]; graph.list.len()];
+    for (id, &(from, to)) in edges.iter().enumerate() {
+        adjacent[from].push((to, id));
+        if !directed && from != to {
+            adjacent[to].push((from, id));
+        }
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut next = vec![0; graph.list.len()];
+    let mut stack = vec![start];
+    let mut out = vec![];
+
+    while let Some(&curr) = stack.last() {
+        // skip edges already walked from the other end
+        while next[curr] < adjacent[curr].len() && used[adjacent[curr][next[curr]].1] {
+            next[curr] += 1;
+        }
+
+        match adjacent[curr].get(next[curr]) {
+            Some(&(to, id)) => {
+                used[id] = true;
+                stack.push(to);
+            }
+            None => {
+                out.push(curr);
+                stack.pop();
+            }
+        }
+    }
+
+    out.into_iter().rev().collect()
+}
+
+pub fn eulerian_path(graph: &WeightedAdjacencyList, directed: bool) -> Result<Vec<usize>, Error> {
+    let start = check(graph, directed, false)?;
+    Ok(hierholzer(graph, directed, start))
+}
+
+pub fn eulerian_circuit(
+    graph: &WeightedAdjacencyList,
+    directed: bool,
+) -> Result<Vec<usize>, Error> {
+    let start = check(graph, directed, true)?;
+    Ok(hierholzer(graph, directed, start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn graph(len: usize, edges: &[(usize, usize)]) -> WeightedAdjacencyList {
+        let mut list = vec![vec![]; len];
+        for &(from, to) in edges {
+            list[from].push(GraphEdge { to, weight: 1 });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    fn assert_walks(graph: &WeightedAdjacencyList, directed: bool, walk: &[usize]) {
+        let mut remaining = edges(graph, directed);
+        assert_eq!(walk.len(), remaining.len() + 1);
+
+        for w in walk.windows(2) {
+            let position = remaining
+                .iter()
+                .position(|&(a, b)| (a, b) == (w[0], w[1]) || (!directed && (b, a) == (w[0], w[1])))
+                .unwrap();
+            remaining.swap_remove(position);
+        }
+    }
+
+    #[test]
+    fn test_directed() {
+        // 0 -> 1 -> 2 -> 0 -> 3 -> 4 -> 0
+        let bowtie = graph(5, &[(0, 1), (1, 2), (2, 0), (0, 3), (3, 4), (4, 0)]);
+        let circuit = eulerian_circuit(&bowtie, true).unwrap();
+        assert_eq!(circuit.first(), circuit.last());
+        assert_walks(&bowtie, true, &circuit);
+
+        let mut open = bowtie.clone();
+        open.list[4].clear();
+        assert_eq!(
+            eulerian_circuit(&open, true),
+            Err(Error::Unbalanced(vec![0, 4]))
+        );
+        let path = eulerian_path(&open, true).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (0, 4));
+        assert_walks(&open, true, &path);
+
+        let fixture = crate::tests::graph::directed_list();
+        assert!(matches!(
+            eulerian_path(&fixture, true),
+            Err(Error::Unbalanced(_))
+        ));
+        assert_eq!(eulerian_path(&graph(3, &[]), true), Err(Error::NoEdges));
+        assert_eq!(
+            eulerian_path(&graph(4, &[(0, 1), (2, 3)]), true),
+            Err(Error::Disconnected)
+        );
+    }
+
+    #[test]
+    fn test_undirected() {
+        // the house of Santa Claus, drawable in one stroke from a bottom corner
+        //     (4)
+        //    /   \
+        //  (2) - (3)
+        //   | \ / |
+        //   | / \ |
+        //  (0) - (1)
+        let house = graph(
+            5,
+            &[
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (2, 4),
+                (3, 4),
+            ],
+        );
+        assert_eq!(
+            eulerian_circuit(&house, false),
+            Err(Error::OddDegree(vec![0, 1]))
+        );
+        let path = eulerian_path(&house, false).unwrap();
+        assert_eq!(path[0], 0);
+        assert_eq!(path[path.len() - 1], 1);
+        assert_walks(&house, false, &path);
+
+        // listing both directions does not double the edges
+        let square = graph(
+            4,
+            &[
+                (0, 1),
+                (1, 0),
+                (1, 2),
+                (2, 1),
+                (2, 3),
+                (3, 2),
+                (3, 0),
+                (0, 3),
+            ],
+        );
+        let circuit = eulerian_circuit(&square, false).unwrap();
+        assert_eq!(circuit.len(), 5);
+        assert_walks(&square, false, &circuit);
+
+        // the bridges of Königsberg: two banks joined to the island twice
+        let konigsberg = graph(4, &[(0, 1), (0, 1), (0, 2), (0, 2), (0, 3), (1, 3), (2, 3)]);
+        assert_eq!(
+            eulerian_path(&konigsberg, false),
+            Err(Error::OddDegree(vec![0, 1, 2, 3]))
+        );
+        let mut rebuilt = konigsberg.clone();
+        rebuilt.list[1].push(GraphEdge { to: 2, weight: 1 });
+        rebuilt.list[0].push(GraphEdge { to: 3, weight: 1 });
+        let circuit = eulerian_circuit(&rebuilt, false).unwrap();
+        assert_eq!(circuit.len(), 10);
+        assert_walks(&rebuilt, false, &circuit);
+
+        let fixture = crate::tests::graph::undirected_list();
+        assert_eq!(
+            eulerian_path(&fixture, false),
+            Err(Error::OddDegree(vec![1, 3, 4, 5]))
+        );
+    }
+}
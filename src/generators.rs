@@ -0,0 +1,357 @@
+use crate::adjacency_list::{GraphEdge, WeightedAdjacencyList};
+
+// every generator builds a `WeightedAdjacencyList`; `WeightedAdjacencyMatrix::from`
+// turns any of them into a matrix. Weights are drawn from 1..=max_weight and
+// undirected graphs list each edge in both directions with the same weight.
+
+// splitmix64, small and good enough for reproducible test data
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..bound
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be positive");
+        // multiply and shift rather than modulo, which would favour small values
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    // uniform in low..=high
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+fn empty(len: usize) -> WeightedAdjacencyList {
+    WeightedAdjacencyList {
+        list: vec![vec![]; len],
+    }
+}
+
+fn add_edge(graph: &mut WeightedAdjacencyList, from: usize, to: usize, weight: usize) {
+    graph.list[from].push(GraphEdge { to, weight });
+}
+
+fn add_undirected(graph: &mut WeightedAdjacencyList, a: usize, b: usize, weight: usize) {
+    add_edge(graph, a, b, weight);
+    add_edge(graph, b, a, weight);
+}
+
+// Erdős–Rényi: every ordered (directed) or unordered pair is an edge with
+// probability p
+pub fn gnp(
+    rng: &mut Rng,
+    len: usize,
+    p: f64,
+    directed: bool,
+    max_weight: usize,
+) -> WeightedAdjacencyList {
+    let mut graph = empty(len);
+
+    for a in 0..len {
+        for b in 0..len {
+            if a == b || (!directed && b < a) || !rng.chance(p) {
+                continue;
+            }
+
+            let weight = rng.range(1, max_weight);
+            if directed {
+                add_edge(&mut graph, a, b, weight);
+            } else {
+                add_undirected(&mut graph, a, b, weight);
+            }
+        }
+    }
+
+    graph
+}
+
+// Barabási–Albert: starts from a clique of m + 1 vertices, then each new vertex
+// attaches to m distinct vertices picked proportionally to their degree
+pub fn barabasi_albert(
+    rng: &mut Rng,
+    len: usize,
+    m: usize,
+    max_weight: usize,
+) -> WeightedAdjacencyList {
+    assert!(m >= 1, "every new vertex needs at least one edge");
+    let mut graph = empty(len);
+    // each vertex appears once per edge end, so a uniform pick is degree biased
+    let mut ends = vec![];
+
+    let seed = (m + 1).min(len);
+    for a in 0..seed {
+        for b in a + 1..seed {
+            add_undirected(&mut graph, a, b, rng.range(1, max_weight));
+            ends.extend([a, b]);
+        }
+    }
+
+    for v in seed..len {
+        let mut targets: Vec<usize> = vec![];
+        while targets.len() < m {
+            let target = ends[rng.below(ends.len())];
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+
+        for target in targets {
+            add_undirected(&mut graph, v, target, rng.range(1, max_weight));
+            ends.extend([v, target]);
+        }
+    }
+
+    graph
+}
+
+// edges only go forwards in a hidden random order, so the result is acyclic
+pub fn random_dag(rng: &mut Rng, len: usize, p: f64, max_weight: usize) -> WeightedAdjacencyList {
+    let mut order: Vec<usize> = (0..len).collect();
+    rng.shuffle(&mut order);
+    let mut graph = empty(len);
+
+    for i in 0..len {
+        for j in i + 1..len {
+            if rng.chance(p) {
+                add_edge(&mut graph, order[i], order[j], rng.range(1, max_weight));
+            }
+        }
+    }
+
+    graph
+}
+
+// vertex (row, column) is row * columns + column, joined to its four neighbours
+pub fn grid(
+    rng: &mut Rng,
+    rows: usize,
+    columns: usize,
+    max_weight: usize,
+) -> WeightedAdjacencyList {
+    let mut graph = empty(rows * columns);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            let v = row * columns + column;
+            if column + 1 < columns {
+                add_undirected(&mut graph, v, v + 1, rng.range(1, max_weight));
+            }
+            if row + 1 < rows {
+                add_undirected(&mut graph, v, v + columns, rng.range(1, max_weight));
+            }
+        }
+    }
+
+    graph
+}
+
+// points thrown into the unit square, joined when closer than radius; the
+// weight is the distance scaled to 1..=max_weight
+pub fn random_geometric(
+    rng: &mut Rng,
+    len: usize,
+    radius: f64,
+    max_weight: usize,
+) -> (WeightedAdjacencyList, Vec<(f64, f64)>) {
+    let points: Vec<(f64, f64)> = (0..len).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+    let mut graph = empty(len);
+
+    for a in 0..len {
+        for b in a + 1..len {
+            let distance =
+                ((points[a].0 - points[b].0).powi(2) + (points[a].1 - points[b].1).powi(2)).sqrt();
+            if distance < radius {
+                let weight =
+                    ((distance / radius * max_weight as f64).ceil() as usize).clamp(1, max_weight);
+                add_undirected(&mut graph, a, b, weight);
+            }
+        }
+    }
+
+    (graph, points)
+}
+
+pub fn complete(rng: &mut Rng, len: usize, max_weight: usize) -> WeightedAdjacencyList {
+    gnp(rng, len, 1.0, false, max_weight)
+}
+
+// vertex 0 is the centre
+pub fn star(rng: &mut Rng, len: usize, max_weight: usize) -> WeightedAdjacencyList {
+    let mut graph = empty(len);
+    for v in 1..len {
+        add_undirected(&mut graph, 0, v, rng.range(1, max_weight));
+    }
+    graph
+}
+
+pub fn path(rng: &mut Rng, len: usize, max_weight: usize) -> WeightedAdjacencyList {
+    let mut graph = empty(len);
+    for v in 1..len {
+        add_undirected(&mut graph, v - 1, v, rng.range(1, max_weight));
+    }
+    graph
+}
+
+pub fn cycle(rng: &mut Rng, len: usize, max_weight: usize) -> WeightedAdjacencyList {
+    let mut graph = path(rng, len, max_weight);
+    if len > 2 {
+        add_undirected(&mut graph, len - 1, 0, rng.range(1, max_weight));
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adjacency_matrix::WeightedAdjacencyMatrix, centrality::out_degrees,
+        connectivity::connected_components, dag::topological_sort, dijkstra_list::dijkstra_tree,
+    };
+
+    fn edge_count(graph: &WeightedAdjacencyList) -> usize {
+        graph.list.iter().map(|edges| edges.len()).sum()
+    }
+
+    fn is_symmetric(graph: &WeightedAdjacencyList) -> bool {
+        let matrix = WeightedAdjacencyMatrix::from(graph);
+        (0..matrix.matrix.len())
+            .all(|a| (0..matrix.matrix.len()).all(|b| matrix.matrix[a][b] == matrix.matrix[b][a]))
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let xs: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(xs, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(
+            xs,
+            (0..5).map(|_| Rng::new(43).next_u64()).collect::<Vec<_>>()
+        );
+
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[a.below(4)] += 1;
+        }
+        assert!(counts.iter().all(|&count| (800..1200).contains(&count)));
+        assert!((0..1000).all(|_| (3..=5).contains(&a.range(3, 5))));
+
+        let mut items: Vec<usize> = (0..10).collect();
+        a.shuffle(&mut items);
+        assert_ne!(items, (0..10).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_gnp() {
+        let directed = gnp(&mut Rng::new(1), 50, 0.1, true, 10);
+        let again = gnp(&mut Rng::new(1), 50, 0.1, true, 10);
+        assert_eq!(
+            WeightedAdjacencyMatrix::from(&directed).matrix,
+            WeightedAdjacencyMatrix::from(&again).matrix
+        );
+        // about 0.1 * 50 * 49 edges
+        assert!((150..350).contains(&edge_count(&directed)));
+        assert!(directed
+            .list
+            .iter()
+            .flatten()
+            .all(|edge| (1..=10).contains(&edge.weight)));
+
+        let undirected = gnp(&mut Rng::new(1), 50, 0.1, false, 10);
+        assert!(is_symmetric(&undirected));
+        assert_eq!(edge_count(&gnp(&mut Rng::new(1), 10, 0.0, true, 10)), 0);
+        assert_eq!(edge_count(&complete(&mut Rng::new(1), 10, 10)), 90);
+    }
+
+    #[test]
+    fn test_barabasi_albert() {
+        let graph = barabasi_albert(&mut Rng::new(7), 200, 2, 5);
+        assert!(is_symmetric(&graph));
+        // three seed edges plus two for each of the other 197 vertices
+        assert_eq!(edge_count(&graph), 2 * (3 + 2 * 197));
+        assert_eq!(connected_components(&graph).count, 1);
+
+        // preferential attachment grows hubs far above the minimum degree
+        assert!(out_degrees(&graph).into_iter().max().unwrap() > 15);
+    }
+
+    #[test]
+    fn test_random_dag() {
+        for seed in 0..5 {
+            let graph = random_dag(&mut Rng::new(seed), 30, 0.3, 9);
+            assert!(topological_sort(&graph).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let rng = &mut Rng::new(3);
+
+        let grid = grid(rng, 3, 4, 1);
+        assert_eq!(edge_count(&grid), 2 * (3 * 3 + 2 * 4));
+        assert_eq!(dijkstra_tree(0, &grid).dists[11], 5);
+
+        let star = star(rng, 6, 1);
+        assert_eq!(out_degrees(&star), vec![5, 1, 1, 1, 1, 1]);
+
+        let path = path(rng, 5, 1);
+        assert_eq!(dijkstra_tree(0, &path).dists, vec![0, 1, 2, 3, 4]);
+
+        let cycle = cycle(rng, 5, 1);
+        assert_eq!(dijkstra_tree(0, &cycle).dists, vec![0, 1, 2, 2, 1]);
+        assert!([&grid, &star, &path, &cycle]
+            .iter()
+            .all(|graph| is_symmetric(graph)));
+    }
+
+    #[test]
+    fn test_random_geometric() {
+        let radius = 0.3;
+        let (graph, points) = random_geometric(&mut Rng::new(11), 100, radius, 10);
+        assert!(is_symmetric(&graph));
+
+        for a in 0..100 {
+            for b in a + 1..100 {
+                let distance = ((points[a].0 - points[b].0).powi(2)
+                    + (points[a].1 - points[b].1).powi(2))
+                .sqrt();
+                assert_eq!(
+                    graph.list[a].iter().any(|edge| edge.to == b),
+                    distance < radius
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::adjacency_matrix::WeightedAdjacencyMatrix;
+
+pub const MAX_VERTICES: usize = 20;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    TooManyVertices,
+    NoPath,
+    // every tour costs more than a usize holds
+    Overflow,
+}
+
+fn edge(graph: &WeightedAdjacencyMatrix, from: usize, to: usize) -> Option<usize> {
+    graph.matrix[from][to]
+}
+
+// ends[mask] has bit v set when some path visits exactly `mask` and stops at v
+pub fn hamiltonian_path(graph: &WeightedAdjacencyMatrix) -> Result<Vec<usize>, Error> {
+    let len = graph.matrix.len();
+    if len > MAX_VERTICES {
+        return Err(Error::TooManyVertices);
+    }
+    if len == 0 {
+        return Err(Error::NoPath);
+    }
+
+    let full = (1usize << len) - 1;
+    let mut ends = vec![0u32; full + 1];
+    for v in 0..len {
+        ends[1 << v] = 1 << v;
+    }
+
+    for mask in 1..=full {
+        for v in 0..len {
+            if ends[mask] & (1 << v) == 0 {
+                continue;
+            }
+
+            for to in 0..len {
+                if mask & (1 << to) == 0 && edge(graph, v, to).is_some() {
+                    ends[mask | (1 << to)] |= 1 << to;
+                }
+            }
+        }
+    }
+
+    let mut v = (0..len)
+        .find(|&v| ends[full] & (1 << v) != 0)
+        .ok_or(Error::NoPath)?;
+    let mut mask = full;
+    let mut out = vec![v];
+
+    // walk back through any predecessor that could have been extended to v
+    while mask.count_ones() > 1 {
+        let rest = mask & !(1 << v);
+        let prev = (0..len)
+            .find(|&u| ends[rest] & (1 << u) != 0 && edge(graph, u, v).is_some())
+            .unwrap();
+        out.push(prev);
+        mask = rest;
+        v = prev;
+    }
+
+    Ok(out.into_iter().rev().collect())
+}
+
+// Held-Karp over the vertices other than the start 0: cost[mask][v] is the
+// cheapest way to leave 0, visit `mask` and stop at v
+pub fn travelling_salesman(graph: &WeightedAdjacencyMatrix) -> Result<(usize, Vec<usize>), Error> {
+    let len = graph.matrix.len();
+    if len > MAX_VERTICES {
+        return Err(Error::TooManyVertices);
+    }
+    if len == 0 {
+        return Err(Error::NoPath);
+    }
+    if len == 1 {
+        return Ok((0, vec![0]));
+    }
+
+    let others = len - 1;
+    let full = (1usize << others) - 1;
+    let mut cost = vec![vec![usize::MAX; others]; full + 1];
+    let mut parent = vec![vec![usize::MAX; others]; full + 1];
+
+    for v in 0..others {
+        if let Some(weight) = edge(graph, 0, v + 1) {
+            cost[1 << v][v] = weight;
+        }
+    }
+
+    // a partial cost that overflows only grows from there, so it is dropped
+    // and matters only if no other tour is left at the end
+    let add = |cost: usize, weight: usize| cost.checked_add(weight).filter(|&c| c != usize::MAX);
+    let mut overflowed = false;
+
+    for mask in 1..=full {
+        for v in 0..others {
+            let curr = cost[mask][v];
+            if curr == usize::MAX {
+                continue;
+            }
+
+            for to in 0..others {
+                if mask & (1 << to) != 0 {
+                    continue;
+                }
+                if let Some(weight) = edge(graph, v + 1, to + 1) {
+                    let next = mask | (1 << to);
+                    match add(curr, weight) {
+                        None => overflowed = true,
+                        Some(new) if new < cost[next][to] => {
+                            cost[next][to] = new;
+                            parent[next][to] = v;
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    let (total, mut v) = (0..others)
+        .filter_map(|v| {
+            let back = edge(graph, v + 1, 0)?;
+            if cost[full][v] == usize::MAX {
+                return None;
+            }
+            let total = add(cost[full][v], back);
+            overflowed |= total.is_none();
+            Some((total?, v))
+        })
+        .min()
+        .ok_or(match overflowed {
+            true => Error::Overflow,
+            false => Error::NoPath,
+        })?;
+
+    let mut mask = full;
+    let mut tour = vec![0];
+    while mask != 0 {
+        tour.push(v + 1);
+        let prev = parent[mask][v];
+        mask &= !(1 << v);
+        v = prev;
+    }
+    tour.push(0);
+
+    Ok((total, tour.into_iter().rev().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_path(graph: &WeightedAdjacencyMatrix, path: &[usize]) -> bool {
+        let mut seen = vec![false; graph.matrix.len()];
+        for &v in path {
+            if seen[v] {
+                return false;
+            }
+            seen[v] = true;
+        }
+
+        path.windows(2).all(|w| edge(graph, w[0], w[1]).is_some())
+    }
+
+    #[test]
+    fn test_hamiltonian_path() {
+        let graph = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(3), Some(1), None, None, None, None],
+                vec![None, None, None, None, Some(1), None, None],
+                vec![None, None, Some(7), None, None, None, None],
+                vec![None, None, None, None, None, None, None],
+                vec![None, Some(1), None, Some(5), None, Some(2), None],
+                vec![None, None, Some(18), None, None, None, Some(1)],
+                vec![None, None, None, Some(1), None, None, Some(1)],
+            ],
+        };
+        // 3 is a sink and 0 a source, and 2 can only reach 3 after 5
+        assert_eq!(hamiltonian_path(&graph), Err(Error::NoPath));
+
+        let mut graph = graph;
+        graph.matrix[2][6] = Some(1);
+        let path = hamiltonian_path(&graph).unwrap();
+        assert_eq!(path, vec![0, 1, 4, 5, 2, 6, 3]);
+        assert!(is_path(&graph, &path));
+
+        let big = WeightedAdjacencyMatrix {
+            matrix: vec![vec![Some(1); 21]; 21],
+        };
+        assert_eq!(hamiltonian_path(&big), Err(Error::TooManyVertices));
+    }
+
+    #[test]
+    fn test_travelling_salesman() {
+        let graph = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(10), Some(15), Some(20)],
+                vec![Some(10), None, Some(35), Some(25)],
+                vec![Some(15), Some(35), None, Some(30)],
+                vec![Some(20), Some(25), Some(30), None],
+            ],
+        };
+        let (cost, tour) = travelling_salesman(&graph).unwrap();
+        assert_eq!(cost, 80);
+        assert_eq!(tour.len(), 5);
+        assert_eq!((tour[0], tour[4]), (0, 0));
+        assert!(is_path(&graph, &tour[..4]));
+
+        let mut graph = graph;
+        graph.matrix[3] = vec![None; 4];
+        assert_eq!(travelling_salesman(&graph), Err(Error::NoPath));
+
+        // sixteen vertices on a ring, the ring itself is the only cheap tour
+        let len = 16;
+        let mut matrix = vec![vec![Some(100); len]; len];
+        for v in 0..len {
+            matrix[v][v] = None;
+            matrix[v][(v + 1) % len] = Some(1);
+        }
+        let (cost, tour) = travelling_salesman(&WeightedAdjacencyMatrix { matrix }).unwrap();
+        assert_eq!(cost, len);
+        assert_eq!(tour, (0..len).chain([0]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_travelling_salesman_overflow() {
+        let huge = Some(usize::MAX / 2 + 1);
+        let mut matrix = vec![vec![huge; 3]; 3];
+        (0..3).for_each(|v| matrix[v][v] = None);
+        let graph = WeightedAdjacencyMatrix { matrix };
+        assert_eq!(travelling_salesman(&graph), Err(Error::Overflow));
+
+        // one cheap tour survives next to the ones that overflow
+        let mut graph = graph;
+        graph.matrix[0][1] = Some(1);
+        graph.matrix[1][2] = Some(1);
+        graph.matrix[2][0] = Some(usize::MAX / 2);
+        assert_eq!(
+            travelling_salesman(&graph),
+            Ok((usize::MAX / 2 + 2, vec![0, 1, 2, 0]))
+        );
+    }
+}
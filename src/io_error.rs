@@ -0,0 +1,17 @@
+use std::io;
+
+// what reading or writing the on-disk formats fails with: csr graphs,
+// contraction hierarchies and the paged trees
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    InvalidFormat(&'static str),
+    // a vertex id, weight or count past what the format stores
+    TooLarge(&'static str),
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
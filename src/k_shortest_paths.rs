@@ -0,0 +1,277 @@
+use crate::{
+    adjacency_list::WeightedAdjacencyList,
+    dijkstra_list::{dijkstra_list, dijkstra_tree},
+};
+
+fn path_cost(graph: &WeightedAdjacencyList, path: &[usize]) -> usize {
+    path.windows(2)
+        .map(|w| {
+            graph.list[w[0]]
+                .iter()
+                .filter(|edge| edge.to == w[1])
+                .map(|edge| edge.weight)
+                .min()
+                .unwrap()
+        })
+        .sum()
+}
+
+pub struct YenPaths<'a> {
+    graph: &'a WeightedAdjacencyList,
+    source: usize,
+    destination: usize,
+    found: Vec<Vec<usize>>,
+    candidates: Vec<(usize, Vec<usize>)>,
+    started: bool,
+}
+
+pub fn yen(graph: &WeightedAdjacencyList, source: usize, destination: usize) -> YenPaths<'_> {
+    YenPaths {
+        graph,
+        source,
+        destination,
+        found: vec![],
+        candidates: vec![],
+        started: false,
+    }
+}
+
+impl YenPaths<'_> {
+    fn spur_paths(&mut self) {
+        let last = self.found.last().unwrap().clone();
+
+        for i in 0..last.len() - 1 {
+            let spur = last[i];
+            let root = &last[..=i];
+            let mut graph = self.graph.clone();
+
+            // don't leave the root the same way an earlier path did
+            for path in self.found.iter() {
+                if path.len() > i + 1 && &path[..=i] == root {
+                    graph.list[spur].retain(|edge| edge.to != path[i + 1]);
+                }
+            }
+
+            // and never go back through the root, so paths stay loopless
+            for &v in root[..i].iter() {
+                graph.list[v].clear();
+            }
+            for edges in graph.list.iter_mut() {
+                edges.retain(|edge| !root[..i].contains(&edge.to));
+            }
+
+            let spur_path = dijkstra_list(spur, self.destination, graph);
+            if spur_path.is_empty() {
+                continue;
+            }
+
+            let mut path = root[..i].to_vec();
+            path.extend(spur_path);
+
+            if !self.found.contains(&path) && !self.candidates.iter().any(|(_, p)| *p == path) {
+                self.candidates.push((path_cost(self.graph, &path), path));
+            }
+        }
+    }
+}
+
+impl Iterator for YenPaths<'_> {
+    type Item = (usize, Vec<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+
+            let path = if self.source == self.destination {
+                vec![self.source]
+            } else {
+                dijkstra_list(self.source, self.destination, self.graph.clone())
+            };
+            if path.is_empty() {
+                return None;
+            }
+
+            self.found.push(path.clone());
+            return Some((path_cost(self.graph, &path), path));
+        }
+
+        if self.found.is_empty() {
+            return None;
+        }
+
+        self.spur_paths();
+
+        let best = (0..self.candidates.len())
+            .min_by(|&a, &b| self.candidates[a].cmp(&self.candidates[b]))?;
+        let (cost, path) = self.candidates.swap_remove(best);
+        self.found.push(path.clone());
+
+        Some((cost, path))
+    }
+}
+
+pub struct AllShortestPaths {
+    preds: Vec<Vec<usize>>,
+    source: usize,
+    cost: usize,
+    // walks backwards from the destination: (vertex, next predecessor to try)
+    stack: Vec<(usize, usize)>,
+}
+
+pub fn all_shortest_paths(
+    graph: &WeightedAdjacencyList,
+    source: usize,
+    destination: usize,
+) -> AllShortestPaths {
+    let dists = dijkstra_tree(source, graph).dists;
+    let mut preds = vec![vec![]; graph.list.len()];
+
+    for (from, edges) in graph.list.iter().enumerate() {
+        if dists[from] == usize::MAX {
+            continue;
+        }
+
+        for edge in edges.iter() {
+            if dists[from] + edge.weight == dists[edge.to] && !preds[edge.to].contains(&from) {
+                preds[edge.to].push(from);
+            }
+        }
+    }
+
+    let reachable = destination < dists.len() && dists[destination] != usize::MAX;
+
+    AllShortestPaths {
+        preds,
+        source,
+        cost: if reachable { dists[destination] } else { 0 },
+        stack: if reachable {
+            vec![(destination, 0)]
+        } else {
+            vec![]
+        },
+    }
+}
+
+impl Iterator for AllShortestPaths {
+    type Item = (usize, Vec<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(curr, i)) = self.stack.last() {
+            if curr == self.source && i == 0 {
+                let path = self.stack.iter().rev().map(|&(v, _)| v).collect();
+                // mark the source as exhausted so the next call backtracks
+                self.stack.last_mut().unwrap().1 = usize::MAX;
+                return Some((self.cost, path));
+            }
+
+            if curr == self.source || i >= self.preds[curr].len() {
+                self.stack.pop();
+                continue;
+            }
+
+            self.stack.last_mut().unwrap().1 += 1;
+            let next = self.preds[curr][i];
+
+            // zero weight cycles would otherwise loop forever
+            if !self.stack.iter().any(|&(v, _)| v == next) {
+                self.stack.push((next, 0));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn graph(len: usize, edges: &[(usize, usize, usize)]) -> WeightedAdjacencyList {
+        let mut list = vec![vec![]; len];
+        for &(from, to, weight) in edges {
+            list[from].push(GraphEdge { to, weight });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    #[test]
+    fn test_yen() {
+        // the example from Yen's original paper as used on wikipedia,
+        // with C..H renamed to 0..5
+        let graph = graph(
+            6,
+            &[
+                (0, 1, 3),
+                (0, 2, 2),
+                (1, 3, 4),
+                (2, 1, 1),
+                (2, 3, 2),
+                (2, 4, 3),
+                (3, 4, 2),
+                (3, 5, 1),
+                (4, 5, 2),
+            ],
+        );
+
+        let paths: Vec<(usize, Vec<usize>)> = yen(&graph, 0, 5).take(3).collect();
+        assert_eq!(
+            paths,
+            vec![
+                (5, vec![0, 2, 3, 5]),
+                (7, vec![0, 2, 4, 5]),
+                (8, vec![0, 1, 3, 5]),
+            ]
+        );
+
+        let costs: Vec<usize> = yen(&graph, 0, 5).map(|(cost, _)| cost).collect();
+        assert_eq!(costs.len(), 7);
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+
+        let graph = crate::tests::graph::directed_list();
+        assert_eq!(yen(&graph, 6, 0).next(), None);
+        let paths: Vec<Vec<usize>> = yen(&graph, 0, 3).map(|(_, path)| path).collect();
+        assert_eq!(paths[0], vec![0, 2, 3]);
+        assert_eq!(paths.len(), 4);
+    }
+
+    #[test]
+    fn test_all_shortest_paths() {
+        // two ways through the middle and a zero weight shortcut
+        let graph = graph(
+            5,
+            &[
+                (0, 1, 1),
+                (0, 2, 1),
+                (1, 3, 1),
+                (2, 3, 1),
+                (1, 2, 0),
+                (2, 1, 0),
+                (3, 4, 5),
+            ],
+        );
+
+        let mut paths: Vec<(usize, Vec<usize>)> = all_shortest_paths(&graph, 0, 4).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                (7, vec![0, 1, 2, 3, 4]),
+                (7, vec![0, 1, 3, 4]),
+                (7, vec![0, 2, 1, 3, 4]),
+                (7, vec![0, 2, 3, 4]),
+            ]
+        );
+
+        assert_eq!(all_shortest_paths(&graph, 4, 0).count(), 0);
+        assert_eq!(
+            all_shortest_paths(&graph, 3, 3).collect::<Vec<_>>(),
+            vec![(0, vec![3])]
+        );
+
+        let graph = crate::tests::graph::undirected_list();
+        let paths: Vec<(usize, Vec<usize>)> = all_shortest_paths(&graph, 0, 6).collect();
+        assert_eq!(paths, vec![(7, vec![0, 1, 4, 5, 6])]);
+    }
+}
@@ -1,14 +1,47 @@
 pub mod adjacency_list;
 pub mod adjacency_matrix;
+pub mod avl_tree;
+pub mod b_plus_tree;
+pub mod b_tree;
 pub mod binary_search;
+pub mod binary_search_tree;
 pub mod binary_tree;
 pub mod bubble_sort;
+pub mod centrality;
+pub mod colouring;
+pub mod connectivity;
+pub mod contraction_hierarchy;
+pub mod csr;
+pub mod dag;
 pub mod dijkstra_list;
+pub mod disjoint_set;
+pub mod dot;
+pub mod dynamic_graph;
+pub mod eulerian_path;
+pub mod generators;
+pub mod hamiltonian_path;
+pub mod io_error;
+pub mod k_shortest_paths;
 pub mod lru_cache;
+pub mod matching;
+pub mod max_flow;
 pub mod maze_solver;
 pub mod min_heap;
+pub mod mst;
+pub mod ordered_map;
+pub mod page;
+pub mod parallel;
 pub mod queue;
 pub mod quick_sort;
+pub mod red_black_tree;
+pub mod scc;
+pub mod skip_list;
+pub mod splay_tree;
 pub mod stack;
 pub mod tests;
+pub mod traversal;
+pub mod treap;
+pub mod tree_queries;
+pub mod tree_render;
+pub mod tree_serialization;
 pub mod two_crystal_balls;
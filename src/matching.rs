@@ -0,0 +1,387 @@
+use crate::{
+    adjacency_list::WeightedAdjacencyList, adjacency_matrix::WeightedAdjacencyMatrix, queue::Queue,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    NotBipartite,
+    NotSquare,
+    // the cheapest assignment costs more than a usize holds
+    Overflow,
+}
+
+#[derive(Clone, Debug)]
+pub struct Matching {
+    pub mate: Vec<Option<usize>>,
+    // (left, right) with left taken from colour 0 of the two-colouring
+    pub pairs: Vec<(usize, usize)>,
+    pub unmatched: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    // column assigned to each row
+    pub columns: Vec<Option<usize>>,
+    pub cost: usize,
+    // rows left without an allowed column
+    pub unmatched: Vec<usize>,
+}
+
+pub fn two_colouring(graph: &WeightedAdjacencyList) -> Result<Vec<usize>, Error> {
+    let list = graph.undirected().list;
+    let mut colour = vec![None; list.len()];
+
+    for root in 0..list.len() {
+        if colour[root].is_some() {
+            continue;
+        }
+
+        colour[root] = Some(0);
+        let mut queue = Queue::new();
+        queue.enqueue(root);
+
+        while let Some(curr) = queue.deque() {
+            let next_colour = colour[curr].map(|c| 1 - c);
+
+            for edge in list[curr].iter() {
+                match colour[edge.to] {
+                    None => {
+                        colour[edge.to] = next_colour;
+                        queue.enqueue(edge.to);
+                    }
+                    Some(c) if Some(c) != next_colour => return Err(Error::NotBipartite),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(colour.into_iter().map(|c| c.unwrap()).collect())
+}
+
+pub fn is_bipartite(graph: &WeightedAdjacencyList) -> bool {
+    two_colouring(graph).is_ok()
+}
+
+// searches from a free left vertex with an explicit stack of (left vertex,
+// edge cursor), so paths as long as the graph cannot overflow; once a free
+// right vertex turns up, every left vertex on the stack takes the right
+// vertex under its cursor
+fn augment(
+    list: &WeightedAdjacencyList,
+    root: usize,
+    mate: &mut [Option<usize>],
+    dist: &mut [Option<usize>],
+) -> bool {
+    let mut path = vec![(root, 0)];
+
+    while let Some(&(curr, cursor)) = path.last() {
+        let Some(edge) = list.list[curr].get(cursor) else {
+            // dead end, don't come back this phase
+            dist[curr] = None;
+            path.pop();
+            if let Some((_, cursor)) = path.last_mut() {
+                *cursor += 1;
+            }
+            continue;
+        };
+
+        match mate[edge.to] {
+            // a free right vertex ends the augmenting path
+            None => {
+                for &(left, cursor) in path.iter() {
+                    let right = list.list[left][cursor].to;
+                    mate[left] = Some(right);
+                    mate[right] = Some(left);
+                }
+                return true;
+            }
+            Some(other) if dist[other] == dist[curr].map(|d| d + 1) => path.push((other, 0)),
+            Some(_) => path.last_mut().unwrap().1 += 1,
+        }
+    }
+
+    false
+}
+
+pub fn hopcroft_karp(graph: &WeightedAdjacencyList) -> Result<Matching, Error> {
+    let colour = two_colouring(graph)?;
+    let list = graph.undirected();
+    let len = list.list.len();
+    let left: Vec<usize> = (0..len).filter(|&v| colour[v] == 0).collect();
+    let mut mate: Vec<Option<usize>> = vec![None; len];
+
+    loop {
+        // 1. layer the left vertices by alternating path length from the free ones
+        let mut dist = vec![None; len];
+        let mut queue = Queue::new();
+        for &v in left.iter() {
+            if mate[v].is_none() {
+                dist[v] = Some(0);
+                queue.enqueue(v);
+            }
+        }
+
+        let mut found = false;
+        while let Some(curr) = queue.deque() {
+            for edge in list.list[curr].iter() {
+                match mate[edge.to] {
+                    None => found = true,
+                    Some(other) if dist[other].is_none() => {
+                        dist[other] = dist[curr].map(|d| d + 1);
+                        queue.enqueue(other);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if !found {
+            break;
+        }
+
+        // 2. augment along vertex-disjoint shortest paths
+        for &v in left.iter() {
+            if mate[v].is_none() && dist[v].is_some() {
+                augment(&list, v, &mut mate, &mut dist);
+            }
+        }
+    }
+
+    Ok(Matching {
+        pairs: left
+            .iter()
+            .filter_map(|&v| mate[v].map(|m| (v, m)))
+            .collect(),
+        unmatched: (0..len).filter(|&v| mate[v].is_none()).collect(),
+        mate,
+    })
+}
+
+pub fn hungarian(costs: &WeightedAdjacencyMatrix) -> Result<Assignment, Error> {
+    let n = costs.matrix.len();
+    if costs.matrix.iter().any(|row| row.len() != n) {
+        return Err(Error::NotSquare);
+    }
+
+    // forbidden cells cost more than every allowed cell together, so they are
+    // only used when a row has nothing else left. i128 holds that sum and the
+    // potentials for any usize costs
+    let forbidden: i128 = 1 + costs
+        .matrix
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|&c| c as i128)
+        .sum::<i128>();
+    let cost = |i: usize, j: usize| costs.matrix[i][j].map_or(forbidden, |c| c as i128);
+
+    // 1-indexed potentials, row 0 / column 0 act as the virtual start
+    let mut u = vec![0i128; n + 1];
+    let mut v = vec![0i128; n + 1];
+    let mut row_of = vec![0usize; n + 1];
+    let mut way = vec![0usize; n + 1];
+
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut j0 = 0;
+        let mut min = vec![i128::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = i128::MAX;
+            let mut j1 = 0;
+
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+
+                let reduced = cost(i0 - 1, j - 1) - u[i0] - v[j];
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = j0;
+                }
+                if min[j] < delta {
+                    delta = min[j];
+                    j1 = j;
+                }
+            }
+
+            for j in 0..=n {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+
+        // flip the alternating path back to the start
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut columns = vec![None; n];
+    let mut total = 0;
+    for (j, &row) in row_of.iter().enumerate().skip(1) {
+        if let Some(c) = costs.matrix[row - 1][j - 1] {
+            columns[row - 1] = Some(j - 1);
+            total = c.checked_add(total).ok_or(Error::Overflow)?;
+        }
+    }
+
+    Ok(Assignment {
+        unmatched: (0..n).filter(|&i| columns[i].is_none()).collect(),
+        columns,
+        cost: total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn graph(len: usize, edges: &[(usize, usize)]) -> WeightedAdjacencyList {
+        let mut list = vec![vec![]; len];
+        for &(from, to) in edges {
+            list[from].push(GraphEdge { to, weight: 1 });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    #[test]
+    fn test_two_colouring() {
+        // even cycle
+        let square = graph(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(two_colouring(&square), Ok(vec![0, 1, 0, 1]));
+
+        // odd cycle
+        let triangle = graph(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(two_colouring(&triangle), Err(Error::NotBipartite));
+        assert!(!is_bipartite(&crate::tests::graph::undirected_list()));
+    }
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // workers 0..4, tasks 4..9
+        let workers = graph(
+            9,
+            &[
+                (0, 4),
+                (0, 5),
+                (1, 4),
+                (2, 5),
+                (2, 6),
+                (3, 6),
+                (3, 7),
+                (3, 8),
+            ],
+        );
+        let matching = hopcroft_karp(&workers).unwrap();
+        assert_eq!(matching.pairs.len(), 4);
+        assert_eq!(matching.unmatched.len(), 1);
+        for &(left, right) in matching.pairs.iter() {
+            assert_eq!(matching.mate[right], Some(left));
+            assert!(workers.list[left].iter().any(|edge| edge.to == right));
+        }
+
+        // 1 and 2 both only know 3
+        let star = graph(5, &[(0, 3), (1, 3), (2, 3), (0, 4)]);
+        let matching = hopcroft_karp(&star).unwrap();
+        assert_eq!(matching.pairs.len(), 2);
+        assert_eq!(matching.unmatched.len(), 1);
+
+        let triangle = graph(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert!(hopcroft_karp(&triangle).is_err());
+    }
+
+    #[test]
+    fn test_hungarian() {
+        let costs = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![Some(9), Some(2), Some(7), Some(8)],
+                vec![Some(6), Some(4), Some(3), Some(7)],
+                vec![Some(5), Some(8), Some(1), Some(8)],
+                vec![Some(7), Some(6), Some(9), Some(4)],
+            ],
+        };
+        let assignment = hungarian(&costs).unwrap();
+        assert_eq!(assignment.cost, 13);
+        assert_eq!(assignment.columns, vec![Some(1), Some(0), Some(2), Some(3)]);
+        assert!(assignment.unmatched.is_empty());
+
+        // nobody but row 0 may take column 0, and rows 1 and 2 fight over 1
+        let costs = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![Some(3), Some(1), None],
+                vec![None, Some(2), None],
+                vec![None, Some(5), None],
+            ],
+        };
+        let assignment = hungarian(&costs).unwrap();
+        assert_eq!(assignment.columns, vec![Some(0), Some(1), None]);
+        assert_eq!(assignment.cost, 5);
+        assert_eq!(assignment.unmatched, vec![2]);
+
+        let costs = WeightedAdjacencyMatrix {
+            matrix: vec![vec![Some(1), Some(2)]],
+        };
+        assert!(matches!(hungarian(&costs), Err(Error::NotSquare)));
+    }
+
+    #[test]
+    fn test_hungarian_large_costs() {
+        // every cell together overflows an i64, the cheapest choice does not
+        let max = usize::MAX;
+        let costs = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![Some(max), Some(1), None],
+                vec![Some(2), Some(max), Some(max)],
+                vec![None, Some(max), Some(5)],
+            ],
+        };
+        let assignment = hungarian(&costs).unwrap();
+        assert_eq!(assignment.columns, vec![Some(1), Some(0), Some(2)]);
+        assert_eq!(assignment.cost, 8);
+
+        let costs = WeightedAdjacencyMatrix {
+            matrix: vec![vec![Some(max), None], vec![None, Some(1)]],
+        };
+        assert!(matches!(hungarian(&costs), Err(Error::Overflow)));
+    }
+
+    #[test]
+    fn test_hopcroft_karp_long_path() {
+        // the path len-2, len-3, .., 1, 0, len-1: the first phase matches 0
+        // to 1, 2 to 3 and so on, which leaves len-2 and len-1 free at the two
+        // ends of one augmenting path through every vertex
+        let len = 300_000;
+        let mut list: Vec<Vec<GraphEdge>> = (1..len - 1)
+            .map(|to| vec![GraphEdge { to, weight: 1 }])
+            .collect();
+        list[0].push(GraphEdge {
+            to: len - 1,
+            weight: 1,
+        });
+        list.extend([vec![], vec![]]);
+
+        let matching = hopcroft_karp(&WeightedAdjacencyList { list }).unwrap();
+        assert_eq!(matching.pairs.len(), len / 2);
+        assert!(matching.unmatched.is_empty());
+        assert_eq!(matching.mate[0], Some(len - 1));
+    }
+}
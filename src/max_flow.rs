@@ -0,0 +1,404 @@
+use crate::{
+    adjacency_list::WeightedAdjacencyList,
+    adjacency_matrix::{bfs_tree, WeightedAdjacencyMatrix},
+    queue::Queue,
+};
+
+#[derive(Clone, Debug)]
+pub struct MaxFlow {
+    pub value: usize,
+    // shaped like the input: flows[u][v] for a matrix, flows[u][i] for the
+    // i-th edge of u in a list
+    pub flows: Vec<Vec<usize>>,
+    // vertices on the source side of a minimum cut
+    pub min_cut: Vec<usize>,
+}
+
+pub fn edmonds_karp(graph: &WeightedAdjacencyMatrix, source: usize, sink: usize) -> MaxFlow {
+    let len = graph.matrix.len();
+    let mut flows = vec![vec![0; len]; len];
+    let mut value = 0;
+
+    // capacity left on every pair, kept up to date along each augmenting path
+    let mut residual = WeightedAdjacencyMatrix {
+        matrix: graph
+            .matrix
+            .iter()
+            .map(|row| row.iter().map(|c| c.filter(|&c| c > 0)).collect())
+            .collect(),
+    };
+
+    loop {
+        let tree = bfs_tree(&residual, source);
+        if tree.prev[sink].is_none() || source == sink {
+            let mut min_cut = tree.order;
+            min_cut.sort();
+            return MaxFlow {
+                value,
+                flows,
+                min_cut,
+            };
+        }
+
+        let mut path = vec![sink];
+        while let Some(prev) = tree.prev[path[path.len() - 1]] {
+            path.push(prev);
+        }
+        path.reverse();
+
+        let bottleneck = path
+            .windows(2)
+            .map(|w| residual.matrix[w[0]][w[1]].unwrap())
+            .min()
+            .unwrap();
+
+        for w in path.windows(2) {
+            let (u, v) = (w[0], w[1]);
+            // cancel flow going the other way before adding new flow
+            let cancelled = bottleneck.min(flows[v][u]);
+            flows[v][u] -= cancelled;
+            flows[u][v] += bottleneck - cancelled;
+
+            let left = residual.matrix[u][v].unwrap() - bottleneck;
+            residual.matrix[u][v] = (left > 0).then_some(left);
+            residual.matrix[v][u] = Some(residual.matrix[v][u].unwrap_or(0) + bottleneck);
+        }
+
+        value += bottleneck;
+    }
+}
+
+struct Arc {
+    to: usize,
+    rev: usize,
+    capacity: usize,
+    edge: Option<usize>,
+}
+
+struct Network {
+    arcs: Vec<Vec<Arc>>,
+}
+
+impl Network {
+    fn new(graph: &WeightedAdjacencyList) -> Self {
+        let mut arcs: Vec<Vec<Arc>> = (0..graph.list.len()).map(|_| vec![]).collect();
+
+        for (from, edges) in graph.list.iter().enumerate() {
+            for (i, edge) in edges.iter().enumerate() {
+                let forward = arcs[from].len();
+                let backward = arcs[edge.to].len() + usize::from(from == edge.to);
+                arcs[from].push(Arc {
+                    to: edge.to,
+                    rev: backward,
+                    capacity: edge.weight,
+                    edge: Some(i),
+                });
+                arcs[edge.to].push(Arc {
+                    to: from,
+                    rev: forward,
+                    capacity: 0,
+                    edge: None,
+                });
+            }
+        }
+
+        Self { arcs }
+    }
+
+    fn push(&mut self, from: usize, i: usize, amount: usize) {
+        let (to, rev) = (self.arcs[from][i].to, self.arcs[from][i].rev);
+        self.arcs[from][i].capacity -= amount;
+        self.arcs[to][rev].capacity += amount;
+    }
+
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.arcs.len()];
+        level[source] = Some(0);
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+
+        while let Some(curr) = queue.deque() {
+            for arc in self.arcs[curr].iter() {
+                if arc.capacity > 0 && level[arc.to].is_none() {
+                    level[arc.to] = level[curr].map(|l| l + 1);
+                    queue.enqueue(arc.to);
+                }
+            }
+        }
+
+        level
+    }
+
+    fn into_max_flow(self, graph: &WeightedAdjacencyList, source: usize, value: usize) -> MaxFlow {
+        let levels = self.levels(source);
+        let mut flows: Vec<Vec<usize>> = graph
+            .list
+            .iter()
+            .map(|edges| vec![0; edges.len()])
+            .collect();
+
+        for (from, arcs) in self.arcs.iter().enumerate() {
+            for arc in arcs.iter() {
+                if let Some(i) = arc.edge {
+                    flows[from][i] = graph.list[from][i].weight - arc.capacity;
+                }
+            }
+        }
+
+        MaxFlow {
+            value,
+            flows,
+            min_cut: (0..levels.len()).filter(|&v| levels[v].is_some()).collect(),
+        }
+    }
+}
+
+// one augmenting path through the level graph, walked with an explicit stack
+// of the arcs taken so a path as long as the graph cannot overflow. a dead end
+// is backed out of and the arc into it skipped from then on
+fn blocking_flow(
+    network: &mut Network,
+    level: &[Option<usize>],
+    next: &mut [usize],
+    source: usize,
+    sink: usize,
+) -> usize {
+    let mut path: Vec<(usize, usize)> = vec![];
+    let mut curr = source;
+
+    while curr != sink {
+        let Some(arc) = network.arcs[curr].get(next[curr]) else {
+            let Some((prev, _)) = path.pop() else {
+                return 0;
+            };
+            next[prev] += 1;
+            curr = prev;
+            continue;
+        };
+
+        if arc.capacity > 0 && level[arc.to] == level[curr].map(|l| l + 1) {
+            path.push((curr, next[curr]));
+            curr = arc.to;
+        } else {
+            next[curr] += 1;
+        }
+    }
+
+    let pushed = path
+        .iter()
+        .map(|&(from, i)| network.arcs[from][i].capacity)
+        .min()
+        .unwrap_or(0);
+    for &(from, i) in path.iter() {
+        network.push(from, i, pushed);
+    }
+    pushed
+}
+
+pub fn dinic(graph: &WeightedAdjacencyList, source: usize, sink: usize) -> MaxFlow {
+    let mut network = Network::new(graph);
+    let mut value = 0;
+
+    if source == sink {
+        return network.into_max_flow(graph, source, value);
+    }
+
+    loop {
+        let level = network.levels(source);
+        if level[sink].is_none() {
+            break;
+        }
+
+        let mut next = vec![0; graph.list.len()];
+        loop {
+            let pushed = blocking_flow(&mut network, &level, &mut next, source, sink);
+            if pushed == 0 {
+                break;
+            }
+            value += pushed;
+        }
+    }
+
+    network.into_max_flow(graph, source, value)
+}
+
+pub fn push_relabel(graph: &WeightedAdjacencyList, source: usize, sink: usize) -> MaxFlow {
+    let len = graph.list.len();
+    let mut network = Network::new(graph);
+
+    if source == sink {
+        return network.into_max_flow(graph, source, 0);
+    }
+
+    let mut height = vec![0; len];
+    let mut excess = vec![0; len];
+    let mut current = vec![0; len];
+    let mut active = Queue::new();
+    height[source] = len;
+
+    for i in 0..network.arcs[source].len() {
+        let arc = &network.arcs[source][i];
+        let (to, capacity) = (arc.to, arc.capacity);
+        if capacity > 0 {
+            network.push(source, i, capacity);
+            if excess[to] == 0 && to != sink && to != source {
+                active.enqueue(to);
+            }
+            excess[to] += capacity;
+        }
+    }
+
+    while let Some(curr) = active.deque() {
+        // discharge
+        while excess[curr] > 0 {
+            if current[curr] == network.arcs[curr].len() {
+                // relabel
+                height[curr] = 1 + network.arcs[curr]
+                    .iter()
+                    .filter(|arc| arc.capacity > 0)
+                    .map(|arc| height[arc.to])
+                    .min()
+                    .unwrap();
+                current[curr] = 0;
+                continue;
+            }
+
+            let i = current[curr];
+            let arc = &network.arcs[curr][i];
+            let (to, capacity) = (arc.to, arc.capacity);
+
+            if capacity > 0 && height[curr] == height[to] + 1 {
+                let amount = excess[curr].min(capacity);
+                network.push(curr, i, amount);
+                excess[curr] -= amount;
+                if excess[to] == 0 && to != sink && to != source {
+                    active.enqueue(to);
+                }
+                excess[to] += amount;
+            } else {
+                current[curr] += 1;
+            }
+        }
+    }
+
+    network.into_max_flow(graph, source, excess[sink])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn clrs() -> WeightedAdjacencyList {
+        // the flow network from CLRS figure 26.1, max flow 23
+        let edges = [
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 3, 12),
+            (2, 1, 4),
+            (2, 4, 14),
+            (3, 2, 9),
+            (3, 5, 20),
+            (4, 3, 7),
+            (4, 5, 4),
+        ];
+        let mut list = vec![vec![]; 6];
+        for (from, to, weight) in edges {
+            list[from].push(GraphEdge { to, weight });
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    // edmonds-karp flows are per pair of vertices, these per edge of the
+    // list; without parallel edges one maps onto the other
+    fn per_edge(graph: &WeightedAdjacencyList, flow: MaxFlow) -> MaxFlow {
+        let flows: Vec<Vec<usize>> = graph
+            .list
+            .iter()
+            .enumerate()
+            .map(|(from, edges)| edges.iter().map(|edge| flow.flows[from][edge.to]).collect())
+            .collect();
+        // nothing flows where there is no edge
+        assert_eq!(
+            flows.iter().flatten().sum::<usize>(),
+            flow.flows.iter().flatten().sum::<usize>()
+        );
+
+        MaxFlow { flows, ..flow }
+    }
+
+    fn assert_valid(graph: &WeightedAdjacencyList, flow: &MaxFlow, source: usize, sink: usize) {
+        let mut balance = vec![0isize; graph.list.len()];
+
+        for (from, edges) in graph.list.iter().enumerate() {
+            for (i, edge) in edges.iter().enumerate() {
+                assert!(flow.flows[from][i] <= edge.weight);
+                balance[from] -= flow.flows[from][i] as isize;
+                balance[edge.to] += flow.flows[from][i] as isize;
+            }
+        }
+
+        for (v, b) in balance.iter().enumerate() {
+            if v == source {
+                assert_eq!(*b, -(flow.value as isize));
+            } else if v == sink {
+                assert_eq!(*b, flow.value as isize);
+            } else {
+                assert_eq!(*b, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_flow() {
+        let graph = clrs();
+
+        let matrix = WeightedAdjacencyMatrix::from(&graph);
+        for flow in [
+            per_edge(&graph, edmonds_karp(&matrix, 0, 5)),
+            dinic(&graph, 0, 5),
+            push_relabel(&graph, 0, 5),
+        ] {
+            assert_eq!(flow.value, 23);
+            assert_eq!(flow.min_cut, vec![0, 1, 2, 4]);
+            assert_valid(&graph, &flow, 0, 5);
+        }
+    }
+
+    #[test]
+    fn test_max_flow_fixture() {
+        let graph = crate::tests::graph::directed_list();
+
+        for (source, sink, value) in [(0, 6, 1), (0, 3, 2), (6, 0, 0)] {
+            let matrix = WeightedAdjacencyMatrix::from(&graph);
+            for flow in [
+                per_edge(&graph, edmonds_karp(&matrix, source, sink)),
+                dinic(&graph, source, sink),
+                push_relabel(&graph, source, sink),
+            ] {
+                assert_eq!(flow.value, value);
+                assert_valid(&graph, &flow, source, sink);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dinic_long_path() {
+        let len = 300_000;
+        let mut list: Vec<Vec<GraphEdge>> = (1..len)
+            .map(|to| {
+                vec![GraphEdge {
+                    to,
+                    weight: 2 + to % 3,
+                }]
+            })
+            .collect();
+        list.push(vec![]);
+        let graph = WeightedAdjacencyList { list };
+
+        let flow = dinic(&graph, 0, len - 1);
+        assert_eq!(flow.value, 2);
+        assert_eq!(flow.min_cut, vec![0, 1, 2]);
+    }
+}
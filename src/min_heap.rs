@@ -50,19 +50,14 @@ where
         let right_value = self.data.get(right_idx);
         let value = self.data[idx];
 
-        if let Some(right_value) = right_value {
-            if left_value > *right_value && value > *right_value {
-                self.data.swap(idx, right_idx);
-                self.heapify_down(right_idx);
-            } else if *right_value > left_value && value > left_value {
-                self.data.swap(idx, left_idx);
-                self.heapify_down(left_idx);
-            }
-        } else {
-            if left_value < value {
-                self.data.swap(idx, left_idx);
-                self.heapify_down(left_idx);
-            }
+        let (child_idx, child_value) = match right_value {
+            Some(right_value) if *right_value < left_value => (right_idx, *right_value),
+            _ => (left_idx, left_value),
+        };
+
+        if child_value < value {
+            self.data.swap(idx, child_idx);
+            self.heapify_down(child_idx);
         }
     }
 
@@ -125,4 +120,19 @@ mod tests {
         assert_eq!(heap.delete(), None);
         assert_eq!(heap.length, 0);
     }
+
+    #[test]
+    fn test_min_heap_equal_children() {
+        let mut heap = MinHeap::new();
+
+        for value in [1, 2, 2, 3, 3, 3, 0] {
+            heap.insert(value);
+        }
+
+        let mut out = vec![];
+        while let Some(value) = heap.delete() {
+            out.push(value);
+        }
+        assert_eq!(out, vec![0, 1, 2, 2, 3, 3, 3]);
+    }
 }
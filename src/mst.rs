@@ -0,0 +1,192 @@
+use crate::{adjacency_list::WeightedAdjacencyList, disjoint_set::DisjointSet, min_heap::MinHeap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpanningForest {
+    pub edges: Vec<Edge>,
+    pub weight: usize,
+    pub trees: usize,
+}
+
+// every edge is treated as undirected, whether or not the list repeats it in
+// both directions
+pub fn edges(graph: &WeightedAdjacencyList) -> Vec<Edge> {
+    graph
+        .list
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| {
+            edges.iter().map(move |edge| Edge {
+                from,
+                to: edge.to,
+                weight: edge.weight,
+            })
+        })
+        .collect()
+}
+
+fn forest(edges: Vec<Edge>, vertices: usize) -> SpanningForest {
+    SpanningForest {
+        weight: edges.iter().map(|edge| edge.weight).sum(),
+        trees: vertices - edges.len(),
+        edges,
+    }
+}
+
+pub fn prim(graph: &WeightedAdjacencyList) -> SpanningForest {
+    let list = graph.undirected().list;
+    let mut seen = vec![false; list.len()];
+    let mut out = vec![];
+
+    for root in 0..list.len() {
+        if seen[root] {
+            continue;
+        }
+
+        seen[root] = true;
+        let mut heap = MinHeap::new();
+        for edge in list[root].iter() {
+            heap.insert((edge.weight, edge.to, root));
+        }
+
+        while let Some((weight, to, from)) = heap.delete() {
+            if seen[to] {
+                continue;
+            }
+
+            seen[to] = true;
+            out.push(Edge { from, to, weight });
+
+            for edge in list[to].iter() {
+                if !seen[edge.to] {
+                    heap.insert((edge.weight, edge.to, to));
+                }
+            }
+        }
+    }
+
+    forest(out, list.len())
+}
+
+pub fn kruskal(graph: &WeightedAdjacencyList) -> SpanningForest {
+    let mut candidates = edges(graph);
+    candidates.sort_by_key(|edge| edge.weight);
+
+    let mut set = DisjointSet::new(graph.list.len());
+    let mut out = vec![];
+
+    for edge in candidates {
+        if set.union(edge.from, edge.to) {
+            out.push(edge);
+        }
+    }
+
+    forest(out, graph.list.len())
+}
+
+pub fn boruvka(graph: &WeightedAdjacencyList) -> SpanningForest {
+    let candidates = edges(graph);
+    let mut set = DisjointSet::new(graph.list.len());
+    let mut out = vec![];
+
+    loop {
+        // cheapest outgoing edge per component, ties broken by edge index so
+        // that two components never pick different edges between them
+        let mut cheapest: Vec<Option<usize>> = vec![None; graph.list.len()];
+
+        for (i, edge) in candidates.iter().enumerate() {
+            let a = set.find(edge.from);
+            let b = set.find(edge.to);
+            if a == b {
+                continue;
+            }
+
+            for component in [a, b] {
+                let better = match cheapest[component] {
+                    Some(j) => (edge.weight, i) < (candidates[j].weight, j),
+                    None => true,
+                };
+                if better {
+                    cheapest[component] = Some(i);
+                }
+            }
+        }
+
+        let mut merged = false;
+        for i in cheapest.into_iter().flatten() {
+            let edge = candidates[i];
+            if set.union(edge.from, edge.to) {
+                out.push(edge);
+                merged = true;
+            }
+        }
+
+        if !merged {
+            break;
+        }
+    }
+
+    forest(out, graph.list.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn normalized(forest: &SpanningForest) -> Vec<(usize, usize, usize)> {
+        let mut edges: Vec<(usize, usize, usize)> = forest
+            .edges
+            .iter()
+            .map(|edge| (edge.from.min(edge.to), edge.from.max(edge.to), edge.weight))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_mst() {
+        let graph = crate::tests::graph::undirected_list();
+        let expected = vec![
+            (0, 1, 3),
+            (0, 2, 1),
+            (1, 4, 1),
+            (3, 6, 1),
+            (4, 5, 2),
+            (5, 6, 1),
+        ];
+
+        for forest in [prim(&graph), kruskal(&graph), boruvka(&graph)] {
+            assert_eq!(forest.weight, 9);
+            assert_eq!(forest.trees, 1);
+            assert_eq!(normalized(&forest), expected);
+        }
+    }
+
+    #[test]
+    fn test_spanning_forest() {
+        // (0) --- (1)    (2) --- (3) --- (4)    (5)
+        let graph = WeightedAdjacencyList {
+            list: vec![
+                vec![GraphEdge { to: 1, weight: 4 }],
+                vec![],
+                vec![GraphEdge { to: 3, weight: 2 }],
+                vec![GraphEdge { to: 4, weight: 6 }],
+                vec![GraphEdge { to: 2, weight: 1 }],
+                vec![],
+            ],
+        };
+
+        for forest in [prim(&graph), kruskal(&graph), boruvka(&graph)] {
+            assert_eq!(forest.weight, 7);
+            assert_eq!(forest.trees, 3);
+            assert_eq!(normalized(&forest), vec![(0, 1, 4), (2, 3, 2), (2, 4, 1)]);
+        }
+    }
+}
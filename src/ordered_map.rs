@@ -0,0 +1,285 @@
+use std::{
+    collections::BTreeMap,
+    ops::RangeBounds,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    avl_tree::AvlTree, b_plus_tree::BPlusTree, b_tree::BTree, binary_search_tree::BinarySearchTree,
+    generators::Rng, red_black_tree::RedBlackTree, skip_list::SkipList, splay_tree::SplayTree,
+    treap::Treap,
+};
+
+// the map operations every ordered container in this crate has, so one set
+// of tests and workloads runs against all of them
+pub trait OrderedMap<K: Ord, V> {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // lookups take &mut self so self-adjusting maps can reshape on reads
+    fn get(&mut self, key: &K) -> Option<&V>;
+
+    fn contains_key(&mut self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn range<'a, R: RangeBounds<K>>(&'a self, range: R) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+}
+
+// every map already has these as inherent methods of the same name
+macro_rules! ordered_map {
+    ($map:ty, $($generics:tt)*) => {
+        impl<$($generics)*> OrderedMap<K, V> for $map {
+            fn len(&self) -> usize {
+                <$map>::len(self)
+            }
+
+            fn get(&mut self, key: &K) -> Option<&V> {
+                <$map>::get(self, key)
+            }
+
+            fn insert(&mut self, key: K, value: V) -> Option<V> {
+                <$map>::insert(self, key, value)
+            }
+
+            fn remove(&mut self, key: &K) -> Option<V> {
+                <$map>::remove(self, key)
+            }
+
+            fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+            where
+                K: 'a,
+                V: 'a,
+            {
+                <$map>::iter(self)
+            }
+
+            fn range<'a, R: RangeBounds<K>>(
+                &'a self,
+                range: R,
+            ) -> impl Iterator<Item = (&'a K, &'a V)>
+            where
+                K: 'a,
+                V: 'a,
+            {
+                <$map>::range(self, range)
+            }
+        }
+    };
+}
+
+ordered_map!(BTreeMap<K, V>, K: Ord, V);
+ordered_map!(BinarySearchTree<K, V>, K: Ord, V);
+ordered_map!(AvlTree<K, V>, K: Ord, V);
+ordered_map!(RedBlackTree<K, V>, K: Ord, V);
+ordered_map!(SplayTree<K, V>, K: Ord, V);
+ordered_map!(Treap<K, V>, K: Ord, V);
+ordered_map!(SkipList<K, V>, K: Ord, V);
+ordered_map!(BTree<K, V, ORDER>, K: Ord, V, const ORDER: usize);
+ordered_map!(BPlusTree<K, V, ORDER>, K: Ord + Clone, V, const ORDER: usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Get(usize),
+    Insert(usize, usize),
+    Remove(usize),
+    // the keys in low..high
+    Range(usize, usize),
+}
+
+// keys come from 0..key_space; with probability `locality` an operation
+// reuses one of the last few keys instead, the pattern splay trees are for
+pub fn workload(rng: &mut Rng, len: usize, key_space: usize, locality: f64) -> Vec<Op> {
+    let mut recent = vec![];
+
+    (0..len)
+        .map(|i| {
+            let key = match !recent.is_empty() && rng.chance(locality) {
+                true => recent[rng.below(recent.len())],
+                false => rng.below(key_space),
+            };
+            if recent.len() < 16 {
+                recent.push(key);
+            } else {
+                recent[i % 16] = key;
+            }
+
+            match rng.below(10) {
+                0..=4 => Op::Get(key),
+                5..=7 => Op::Insert(key, rng.below(1000)),
+                8 => Op::Remove(key),
+                _ => Op::Range(key, key + rng.below(20)),
+            }
+        })
+        .collect()
+}
+
+// folds every result into one number, so two maps agree on a workload
+// exactly when their checksums do (up to hash collisions)
+pub fn run<M: OrderedMap<usize, usize>>(map: &mut M, ops: &[Op]) -> u64 {
+    let mut checksum = 0u64;
+
+    for &op in ops {
+        let out = match op {
+            Op::Get(key) => map.get(&key).copied(),
+            Op::Insert(key, value) => map.insert(key, value),
+            Op::Remove(key) => map.remove(&key),
+            Op::Range(low, high) => Some(map.range(low..high).map(|(k, v)| k ^ v).sum()),
+        };
+        let out = out.map_or(0, |out| out as u64 + 1);
+        checksum = checksum.wrapping_mul(0x100000001b3).wrapping_add(out);
+    }
+
+    checksum
+}
+
+// runs a workload on a fresh map, for comparing containers on one pattern
+pub fn bench<M: OrderedMap<usize, usize> + Default>(ops: &[Op]) -> (u64, Duration) {
+    let mut map = M::default();
+    let start = Instant::now();
+    let checksum = run(&mut map, ops);
+    (checksum, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_map<M: OrderedMap<usize, usize> + Default>() {
+        let rng = &mut Rng::new(47);
+
+        for locality in [0.0, 0.9] {
+            let ops = workload(rng, 5000, 500, locality);
+            let mut expected = BTreeMap::new();
+            let checksum = run(&mut expected, &ops);
+
+            let mut map = M::default();
+            assert_eq!(run(&mut map, &ops), checksum);
+            assert_eq!(map.len(), expected.len());
+            assert!(map.iter().eq(expected.iter()));
+            assert!(map.range(100..200).eq(expected.range(100..200)));
+            assert_eq!(bench::<M>(&ops).0, checksum);
+
+            for key in 0..500 {
+                assert_eq!(map.contains_key(&key), expected.contains_key(&key));
+                assert_eq!(map.remove(&key), expected.remove(&key));
+            }
+            assert!(map.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_workload() {
+        let rng = &mut Rng::new(1);
+        let local = workload(rng, 1000, 1 << 40, 0.9);
+        let random = workload(rng, 1000, 1 << 40, 0.0);
+
+        let distinct = |ops: &[Op]| {
+            let mut keys: Vec<_> = ops
+                .iter()
+                .map(|op| match *op {
+                    Op::Get(key) | Op::Insert(key, _) | Op::Remove(key) | Op::Range(key, _) => key,
+                })
+                .collect();
+            keys.sort();
+            keys.dedup();
+            keys.len()
+        };
+        assert_eq!(distinct(&random), 1000);
+        assert!(distinct(&local) < 200);
+    }
+
+    // cargo test --release bench_containers -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_containers() {
+        let rng = &mut Rng::new(47);
+
+        for (name, locality) in [("uniform", 0.0), ("local", 0.9)] {
+            let ops = workload(rng, 200_000, 50_000, locality);
+            let (expected, _) = bench::<BTreeMap<usize, usize>>(&ops);
+
+            let results = [
+                ("BTreeMap", bench::<BTreeMap<usize, usize>>(&ops)),
+                (
+                    "BinarySearchTree",
+                    bench::<BinarySearchTree<usize, usize>>(&ops),
+                ),
+                ("AvlTree", bench::<AvlTree<usize, usize>>(&ops)),
+                ("RedBlackTree", bench::<RedBlackTree<usize, usize>>(&ops)),
+                ("SplayTree", bench::<SplayTree<usize, usize>>(&ops)),
+                ("Treap", bench::<Treap<usize, usize>>(&ops)),
+                ("SkipList", bench::<SkipList<usize, usize>>(&ops)),
+                ("BTree<16>", bench::<BTree<usize, usize, 16>>(&ops)),
+                ("BPlusTree<16>", bench::<BPlusTree<usize, usize, 16>>(&ops)),
+            ];
+
+            println!("{name} keys, {} operations", ops.len());
+            for (map, (checksum, elapsed)) in results {
+                assert_eq!(checksum, expected, "{map} disagrees with BTreeMap");
+                println!("  {map:<18}{elapsed:>12.2?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_std_btree_map() {
+        check_map::<BTreeMap<usize, usize>>();
+    }
+
+    #[test]
+    fn test_binary_search_tree() {
+        check_map::<BinarySearchTree<usize, usize>>();
+    }
+
+    #[test]
+    fn test_avl_tree() {
+        check_map::<AvlTree<usize, usize>>();
+    }
+
+    #[test]
+    fn test_red_black_tree() {
+        check_map::<RedBlackTree<usize, usize>>();
+    }
+
+    #[test]
+    fn test_splay_tree() {
+        check_map::<SplayTree<usize, usize>>();
+    }
+
+    #[test]
+    fn test_treap() {
+        check_map::<Treap<usize, usize>>();
+    }
+
+    #[test]
+    fn test_skip_list() {
+        check_map::<SkipList<usize, usize>>();
+    }
+
+    #[test]
+    fn test_b_tree() {
+        check_map::<BTree<usize, usize, 3>>();
+        check_map::<BTree<usize, usize, 16>>();
+    }
+
+    #[test]
+    fn test_b_plus_tree() {
+        check_map::<BPlusTree<usize, usize, 3>>();
+        check_map::<BPlusTree<usize, usize, 16>>();
+    }
+}
@@ -0,0 +1,468 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+pub use crate::io_error::Error;
+
+const VERSION: u32 = 1;
+
+// little endian values of a fixed width, so every node of a tree encodes to
+// the same number of bytes and page n starts at n * page_size in the file
+pub trait FixedSize: Copy {
+    const SIZE: usize;
+
+    fn encode(&self, out: &mut [u8]);
+    // fails only for values the host cannot represent
+    fn decode(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+macro_rules! fixed_size_int {
+    ($($int:ty),*) => {$(
+        impl FixedSize for $int {
+            const SIZE: usize = std::mem::size_of::<$int>();
+
+            fn encode(&self, out: &mut [u8]) {
+                out[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(bytes: &[u8]) -> Result<Self, Error> {
+                Ok(<$int>::from_le_bytes(bytes[..Self::SIZE].try_into().unwrap()))
+            }
+        }
+    )*};
+}
+
+fixed_size_int!(u8, u16, u32, u64, i32, i64);
+
+// stored as a u64 so files move between 32 and 64 bit hosts, though a 32 bit
+// host refuses values it cannot hold rather than truncating them
+impl FixedSize for usize {
+    const SIZE: usize = 8;
+
+    fn encode(&self, out: &mut [u8]) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        usize::try_from(u64::decode(bytes)?).map_err(|_| Error::TooLarge("usize value"))
+    }
+}
+
+impl FixedSize for () {
+    const SIZE: usize = 0;
+
+    fn encode(&self, _: &mut [u8]) {}
+
+    fn decode(_: &[u8]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+pub(crate) fn encode_all<T: FixedSize>(values: impl IntoIterator<Item = T>, out: &mut [u8]) {
+    for (value, chunk) in values.into_iter().zip(out.chunks_mut(T::SIZE.max(1))) {
+        value.encode(chunk);
+    }
+}
+
+pub(crate) fn decode_all<T: FixedSize>(bytes: &[u8], len: usize) -> Result<Vec<T>, Error> {
+    (0..len).map(|i| T::decode(&bytes[i * T::SIZE..])).collect()
+}
+
+// something that fits in one page of at most SIZE bytes
+pub trait Page: Sized {
+    const SIZE: usize;
+
+    fn encode(&self, page: &mut [u8]);
+    fn decode(page: &[u8]) -> Result<Self, Error>;
+}
+
+// a file of equally sized pages, read and written one page at a time
+#[derive(Debug)]
+pub struct PageFile {
+    file: File,
+    page_size: usize,
+}
+
+impl PageFile {
+    pub fn create<P: AsRef<Path>>(path: P, page_size: usize) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self { file, page_size })
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P, page_size: usize) -> Result<Self, Error> {
+        let file = PageFile {
+            file: OpenOptions::new().read(true).write(true).open(path)?,
+            page_size,
+        };
+        if file.file.metadata()?.len() % page_size as u64 != 0 {
+            return Err(Error::InvalidFormat("file is not a whole number of pages"));
+        }
+        Ok(file)
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    pub fn page_count(&self) -> Result<usize, Error> {
+        Ok((self.file.metadata()?.len() / self.page_size as u64) as usize)
+    }
+
+    pub fn write<P: Page>(&mut self, id: usize, page: &P) -> Result<(), Error> {
+        assert!(P::SIZE <= self.page_size, "page does not fit");
+        let mut bytes = vec![0; self.page_size];
+        page.encode(&mut bytes);
+
+        self.file
+            .seek(SeekFrom::Start((id * self.page_size) as u64))?;
+        self.file.write_all(&bytes)?;
+        Ok(())
+    }
+
+    pub fn read<P: Page>(&mut self, id: usize) -> Result<P, Error> {
+        assert!(P::SIZE <= self.page_size, "page does not fit");
+        let mut bytes = vec![0; self.page_size];
+
+        self.file
+            .seek(SeekFrom::Start((id * self.page_size) as u64))?;
+        self.file.read_exact(&mut bytes)?;
+        P::decode(&bytes)
+    }
+}
+
+// a node of a paged tree, pointing at other nodes by their index
+pub(crate) trait TreePage: Page {
+    fn children(&self) -> &[usize];
+
+    // the leaf to the right, for trees that chain their leaves
+    fn next(&self) -> Option<usize> {
+        None
+    }
+
+    // a copy with every index it holds mapped through `ids`
+    fn renumbered(&self, ids: &[usize]) -> Self;
+}
+
+// page 0 of a saved tree, the nodes follow from page 1 with the root first
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TreeHeader {
+    pub magic: [u8; 4],
+    pub order: u32,
+    pub len: u64,
+    pub nodes: u32,
+}
+
+impl Page for TreeHeader {
+    const SIZE: usize = 24;
+
+    fn encode(&self, page: &mut [u8]) {
+        page[..4].copy_from_slice(&self.magic);
+        VERSION.encode(&mut page[4..]);
+        self.order.encode(&mut page[8..]);
+        self.len.encode(&mut page[12..]);
+        self.nodes.encode(&mut page[20..]);
+    }
+
+    fn decode(page: &[u8]) -> Result<Self, Error> {
+        if u32::decode(&page[4..])? != VERSION {
+            return Err(Error::InvalidFormat("unsupported version"));
+        }
+
+        Ok(Self {
+            magic: page[..4].try_into().unwrap(),
+            order: u32::decode(&page[8..])?,
+            len: u64::decode(&page[12..])?,
+            nodes: u32::decode(&page[20..])?,
+        })
+    }
+}
+
+fn page_size<P: Page>() -> usize {
+    P::SIZE.max(TreeHeader::SIZE)
+}
+
+// one page per node, renumbered breadth first so the root is page 1 and
+// every child comes after its parent; nodes the root does not reach, like
+// freed arena slots, are left out
+pub(crate) fn save_tree<P: TreePage, Q: AsRef<Path>>(
+    path: Q,
+    magic: &[u8; 4],
+    order: usize,
+    len: usize,
+    nodes: &[P],
+    root: usize,
+) -> Result<(), Error> {
+    let mut ids = vec![usize::MAX; nodes.len()];
+    let mut reached = vec![root];
+    ids[root] = 0;
+
+    let mut next = 0;
+    while next < reached.len() {
+        for &child in nodes[reached[next]].children() {
+            ids[child] = reached.len();
+            reached.push(child);
+        }
+        next += 1;
+    }
+
+    let header = TreeHeader {
+        magic: *magic,
+        order: u32::try_from(order).map_err(|_| Error::TooLarge("order"))?,
+        len: len as u64,
+        nodes: u32::try_from(reached.len()).map_err(|_| Error::TooLarge("node count"))?,
+    };
+
+    let mut file = PageFile::create(path, page_size::<P>())?;
+    file.write(0, &header)?;
+    for (id, &node) in reached.iter().enumerate() {
+        file.write(id + 1, &nodes[node].renumbered(&ids))?;
+    }
+    Ok(())
+}
+
+// the element count and the nodes with the root first, their links checked
+// but the tree itself still to be checked by the caller
+pub(crate) fn load_tree<P: TreePage, Q: AsRef<Path>>(
+    path: Q,
+    magic: &[u8; 4],
+    order: usize,
+) -> Result<(usize, Vec<P>), Error> {
+    let mut file = PageFile::open(path, page_size::<P>())?;
+    if file.page_count()? == 0 {
+        return Err(Error::InvalidFormat("missing header"));
+    }
+
+    let header: TreeHeader = file.read(0)?;
+    if &header.magic != magic {
+        return Err(Error::InvalidFormat("not a file of this tree type"));
+    }
+    if header.order as usize != order {
+        return Err(Error::InvalidFormat("tree was saved with another order"));
+    }
+    if file.page_count()? != header.nodes as usize + 1 {
+        return Err(Error::InvalidFormat("page count does not match the header"));
+    }
+    let len = usize::try_from(header.len).map_err(|_| Error::InvalidFormat("length too large"))?;
+
+    let nodes: Vec<P> = (1..=header.nodes as usize)
+        .map(|id| file.read(id))
+        .collect::<Result<_, _>>()?;
+
+    // children always come after their parent, so a bad file cannot loop
+    let bad_link = nodes.iter().enumerate().any(|(id, node)| {
+        node.children()
+            .iter()
+            .any(|&child| child <= id || child >= nodes.len())
+            || node.next().is_some_and(|next| next >= nodes.len())
+    });
+    if nodes.is_empty() || bad_link {
+        return Err(Error::InvalidFormat("node links are out of order"));
+    }
+
+    Ok((len, nodes))
+}
+
+// Default, FromIterator, save and load for the arena backed b-trees, which
+// hold their nodes, root and length in `nodes`, `root` and `len` and provide
+// `new`, `insert` and `is_valid`
+macro_rules! paged_tree {
+    ($tree:ident, $magic:expr, $kind:literal, $($bound:tt)+) => {
+        impl<K: $($bound)+, V, const ORDER: usize> Default for $tree<K, V, ORDER> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<K: $($bound)+, V, const ORDER: usize> FromIterator<(K, V)> for $tree<K, V, ORDER> {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+                let mut tree = Self::new();
+                for (key, value) in iter {
+                    tree.insert(key, value);
+                }
+                tree
+            }
+        }
+
+        impl<K, V, const ORDER: usize> $tree<K, V, ORDER>
+        where
+            K: Ord + $crate::page::FixedSize,
+            V: $crate::page::FixedSize,
+        {
+            pub fn save<P: AsRef<::std::path::Path>>(
+                &self,
+                path: P,
+            ) -> Result<(), $crate::page::Error> {
+                $crate::page::save_tree(path, $magic, ORDER, self.len, &self.nodes, self.root)
+            }
+
+            pub fn load<P: AsRef<::std::path::Path>>(path: P) -> Result<Self, $crate::page::Error> {
+                let (len, nodes) = $crate::page::load_tree(path, $magic, ORDER)?;
+                let tree = Self {
+                    nodes,
+                    free: vec![],
+                    root: 0,
+                    len,
+                };
+
+                match tree.is_valid() {
+                    true => Ok(tree),
+                    false => Err($crate::page::Error::InvalidFormat(concat!(
+                        "nodes do not form a ",
+                        $kind
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+// what both b-trees are tested on: random operations against BTreeMap with
+// every order from the smallest up, ranges, and a save and load round trip
+#[cfg(test)]
+macro_rules! paged_tree_tests {
+    ($tree:ident) => {
+        fn against_btree_map<const ORDER: usize>(seed: u64) {
+            let rng = &mut $crate::generators::Rng::new(seed);
+            let mut tree = $tree::<usize, usize, ORDER>::new();
+            let mut map = ::std::collections::BTreeMap::new();
+
+            for _ in 0..3000 {
+                let key = rng.below(400);
+                if rng.chance(0.55) {
+                    assert_eq!(tree.insert(key, key * 2), map.insert(key, key * 2));
+                } else {
+                    assert_eq!(tree.remove(&key), map.remove(&key));
+                }
+
+                assert!(tree.is_valid());
+                assert_eq!(tree.len(), map.len());
+            }
+
+            assert!(tree.iter().eq(map.iter()));
+            assert!((0..400).all(|key| tree.get(&key) == map.get(&key)));
+
+            for key in 0..400 {
+                assert_eq!(tree.remove(&key), map.remove(&key));
+                assert!(tree.is_valid());
+            }
+            assert!(tree.is_empty());
+            assert_eq!(tree.height(), 1);
+        }
+
+        #[test]
+        fn test_against_btree_map() {
+            against_btree_map::<3>(3);
+            against_btree_map::<4>(4);
+            against_btree_map::<5>(5);
+            against_btree_map::<32>(32);
+        }
+
+        #[test]
+        fn test_range() {
+            use ::std::ops::Bound;
+
+            let map: ::std::collections::BTreeMap<usize, usize> =
+                (0..500).step_by(3).map(|key| (key, key)).collect();
+            let tree: $tree<usize, usize, 4> = map.iter().map(|(&k, &v)| (k, v)).collect();
+
+            for (low, high) in [(0, 0), (3, 3), (4, 100), (99, 300), (0, 600), (498, 499)] {
+                assert!(tree.range(low..high).eq(map.range(low..high)));
+                assert!(tree.range(low..=high).eq(map.range(low..=high)));
+                assert!(tree.range(low..).eq(map.range(low..)));
+                assert!(tree.range(..high).eq(map.range(..high)));
+
+                let bounds = (Bound::Excluded(low), Bound::Included(high));
+                assert!(tree.range(bounds).eq(map.range(bounds)));
+            }
+            assert_eq!($tree::<u8, u8, 3>::new().range(..).next(), None);
+        }
+
+        #[test]
+        fn test_save_load_round_trip() {
+            let name = format!("{}_{}.bin", stringify!($tree), ::std::process::id());
+            let path = ::std::env::temp_dir().join(name);
+            let mut tree: $tree<u32, i64, 5> = (0..1000).map(|key| (key, -(key as i64))).collect();
+            for key in (0..1000).step_by(3) {
+                tree.remove(&key);
+            }
+
+            tree.save(&path).unwrap();
+            let loaded = $tree::<u32, i64, 5>::load(&path);
+            let other_order = $tree::<u32, i64, 6>::load(&path);
+            ::std::fs::remove_file(&path).unwrap();
+
+            let loaded = loaded.unwrap();
+            assert!(loaded.is_valid());
+            assert!(loaded.iter().eq(tree.iter()));
+            assert!(loaded.range(100..200).eq(tree.range(100..200)));
+            assert!(loaded.nodes.len() < tree.nodes.len());
+            assert!(matches!(
+                other_order,
+                Err($crate::page::Error::InvalidFormat(_))
+            ));
+        }
+    };
+}
+
+pub(crate) use paged_tree;
+#[cfg(test)]
+pub(crate) use paged_tree_tests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_size() {
+        let mut bytes = [0; 8];
+        (-2i32).encode(&mut bytes);
+        assert_eq!(bytes[..4], [0xfe, 0xff, 0xff, 0xff]);
+        assert_eq!(i32::decode(&bytes).unwrap(), -2);
+
+        usize::MAX.encode(&mut bytes);
+        assert_eq!(usize::decode(&bytes).unwrap(), usize::MAX);
+        u64::MAX.encode(&mut bytes);
+        match usize::BITS {
+            64 => assert_eq!(usize::decode(&bytes).unwrap(), usize::MAX),
+            _ => assert!(matches!(usize::decode(&bytes), Err(Error::TooLarge(_)))),
+        }
+
+        let mut bytes = [0; 12];
+        encode_all([1u16, 2, 3], &mut bytes);
+        assert_eq!(decode_all::<u16>(&bytes, 3).unwrap(), vec![1, 2, 3]);
+        assert_eq!(decode_all::<()>(&bytes, 2).unwrap(), vec![(), ()]);
+    }
+
+    #[test]
+    fn test_page_file() {
+        let path = std::env::temp_dir().join(format!("page_file_{}.bin", std::process::id()));
+        let header = TreeHeader {
+            magic: *b"TEST",
+            order: 7,
+            len: 1 << 40,
+            nodes: 3,
+        };
+
+        let mut file = PageFile::create(&path, 32).unwrap();
+        file.write(2, &header).unwrap();
+        assert_eq!(file.page_count().unwrap(), 3);
+        assert_eq!(file.read::<TreeHeader>(2).unwrap(), header);
+        assert!(matches!(
+            file.read::<TreeHeader>(0),
+            Err(Error::InvalidFormat(_))
+        ));
+
+        let odd = PageFile::open(&path, 40);
+        let missing = file.read::<TreeHeader>(3);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(odd, Err(Error::InvalidFormat(_))));
+        assert!(matches!(missing, Err(Error::Io(_))));
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{csr::CsrGraph, scc::Components};
+
+fn chunk_size(len: usize, threads: usize) -> usize {
+    len.div_ceil(threads.max(1)).max(1)
+}
+
+// runs `work` over `items` split across `threads` scoped threads and gathers
+// whatever each thread produced
+fn for_each_chunk<T, R, F>(items: &[T], threads: usize, work: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T], &mut Vec<R>) + Sync,
+{
+    if items.is_empty() {
+        return vec![];
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size(items.len(), threads))
+            .map(|chunk| {
+                let work = &work;
+                scope.spawn(move || {
+                    let mut out = vec![];
+                    work(chunk, &mut out);
+                    out
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+pub fn parallel_bfs(graph: &CsrGraph, source: usize, threads: usize) -> Vec<Option<usize>> {
+    let depth: Vec<AtomicU32> = (0..graph.vertex_count())
+        .map(|_| AtomicU32::new(u32::MAX))
+        .collect();
+    depth[source].store(0, Ordering::Relaxed);
+    let mut frontier = vec![source];
+    let mut level = 0;
+
+    while !frontier.is_empty() {
+        level += 1;
+        frontier = for_each_chunk(&frontier, threads, |chunk, next| {
+            for &curr in chunk {
+                for (to, _) in graph.edges(curr) {
+                    // whoever swaps the marker out first owns the vertex
+                    if depth[to]
+                        .compare_exchange(u32::MAX, level, Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        next.push(to);
+                    }
+                }
+            }
+        });
+    }
+
+    depth
+        .into_iter()
+        .map(|d| match d.into_inner() {
+            u32::MAX => None,
+            d => Some(d as usize),
+        })
+        .collect()
+}
+
+pub fn parallel_connected_components(graph: &CsrGraph, threads: usize) -> Components {
+    let len = graph.vertex_count();
+    let labels: Vec<AtomicUsize> = (0..len).map(AtomicUsize::new).collect();
+    let vertices: Vec<usize> = (0..len).collect();
+
+    // label propagation with pointer jumping: every vertex converges on the
+    // smallest id in its component, edges are followed in both directions
+    loop {
+        let changed = AtomicBool::new(false);
+
+        for_each_chunk(&vertices, threads, |chunk, _: &mut Vec<()>| {
+            for &curr in chunk {
+                for (to, _) in graph.edges(curr) {
+                    let a = labels[curr].load(Ordering::Relaxed);
+                    let b = labels[to].load(Ordering::Relaxed);
+                    let min = a.min(b);
+
+                    if labels[curr].fetch_min(min, Ordering::Relaxed) > min
+                        || labels[to].fetch_min(min, Ordering::Relaxed) > min
+                    {
+                        changed.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+
+        for_each_chunk(&vertices, threads, |chunk, _: &mut Vec<()>| {
+            for &curr in chunk {
+                let parent = labels[curr].load(Ordering::Relaxed);
+                let grandparent = labels[parent].load(Ordering::Relaxed);
+                if labels[curr].fetch_min(grandparent, Ordering::Relaxed) > grandparent {
+                    changed.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+
+        if !changed.into_inner() {
+            break;
+        }
+    }
+
+    let mut ids = vec![usize::MAX; len];
+    let mut count = 0;
+    for v in 0..len {
+        let root = labels[v].load(Ordering::Relaxed);
+        if ids[root] == usize::MAX {
+            ids[root] = count;
+            count += 1;
+        }
+        ids[v] = ids[root];
+    }
+
+    Components { count, ids }
+}
+
+fn relax(
+    graph: &CsrGraph,
+    dists: &[AtomicUsize],
+    frontier: &[usize],
+    threads: usize,
+    light: bool,
+    delta: usize,
+) -> Vec<(usize, usize)> {
+    for_each_chunk(frontier, threads, |chunk, updates| {
+        for &curr in chunk {
+            let dist = dists[curr].load(Ordering::Relaxed);
+
+            for (to, weight) in graph.edges(curr) {
+                if (weight <= delta) != light {
+                    continue;
+                }
+
+                let new_dist = dist + weight;
+                if dists[to].fetch_min(new_dist, Ordering::Relaxed) > new_dist {
+                    updates.push((to, new_dist));
+                }
+            }
+        }
+    })
+}
+
+pub fn delta_stepping(
+    graph: &CsrGraph,
+    source: usize,
+    delta: usize,
+    threads: usize,
+) -> Vec<Option<usize>> {
+    let delta = delta.max(1);
+    let dists: Vec<AtomicUsize> = (0..graph.vertex_count())
+        .map(|_| AtomicUsize::new(usize::MAX))
+        .collect();
+    dists[source].store(0, Ordering::Relaxed);
+
+    let mut buckets: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    buckets.insert(0, vec![source]);
+
+    while let Some((bucket, _)) = buckets.first_key_value() {
+        let bucket = *bucket;
+        let mut settled = vec![];
+
+        // light edges can refill the current bucket, so keep going until it stays empty
+        while let Some(mut frontier) = buckets.remove(&bucket) {
+            // entries whose distance dropped into an earlier bucket are stale
+            frontier.retain(|&v| dists[v].load(Ordering::Relaxed) / delta == bucket);
+            frontier.sort_unstable();
+            frontier.dedup();
+            settled.extend(frontier.iter().copied());
+
+            for (v, dist) in relax(graph, &dists, &frontier, threads, true, delta) {
+                buckets.entry(dist / delta).or_default().push(v);
+            }
+        }
+
+        settled.sort_unstable();
+        settled.dedup();
+        for (v, dist) in relax(graph, &dists, &settled, threads, false, delta) {
+            buckets.entry(dist / delta).or_default().push(v);
+        }
+    }
+
+    dists
+        .into_iter()
+        .map(|d| match d.into_inner() {
+            usize::MAX => None,
+            d => Some(d),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adjacency_list::{GraphEdge, WeightedAdjacencyList},
+        connectivity::connected_components,
+        dijkstra_list::dijkstra_tree,
+        traversal::Bfs,
+    };
+
+    // a sparse, deterministic pseudo random graph with a few isolated pieces
+    fn graph(len: usize) -> WeightedAdjacencyList {
+        let mut list = vec![vec![]; len];
+        let mut state: usize = 12345;
+
+        for (from, edges) in list.iter_mut().enumerate() {
+            if from.is_multiple_of(97) {
+                continue;
+            }
+            for _ in 0..3 {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let to = (state >> 33) % len;
+                let weight = (state >> 20) % 20 + 1;
+                if !to.is_multiple_of(97) {
+                    edges.push(GraphEdge { to, weight });
+                }
+            }
+        }
+
+        WeightedAdjacencyList { list }
+    }
+
+    #[test]
+    fn test_parallel_bfs() {
+        let list = graph(2000);
+        let csr = CsrGraph::try_from(&list).unwrap();
+
+        for source in [1, 500] {
+            let mut bfs = Bfs::new(&list, source);
+            bfs.by_ref().for_each(drop);
+            let expected: Vec<Option<usize>> = (0..2000).map(|v| bfs.depth(v)).collect();
+
+            for threads in [1, 4] {
+                assert_eq!(parallel_bfs(&csr, source, threads), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_connected_components() {
+        let list = graph(2000);
+        let csr = CsrGraph::try_from(&list).unwrap();
+        let expected = connected_components(&list);
+
+        for threads in [1, 4] {
+            let components = parallel_connected_components(&csr, threads);
+            assert_eq!(components.count, expected.count);
+            assert_eq!(components.ids, expected.ids);
+        }
+    }
+
+    #[test]
+    fn test_delta_stepping() {
+        let list = graph(2000);
+        let csr = CsrGraph::try_from(&list).unwrap();
+
+        for source in [1, 500] {
+            let expected: Vec<Option<usize>> = dijkstra_tree(source, &list)
+                .dists
+                .into_iter()
+                .map(|d| if d == usize::MAX { None } else { Some(d) })
+                .collect();
+
+            for (delta, threads) in [(1, 1), (5, 4), (100, 3)] {
+                assert_eq!(delta_stepping(&csr, source, delta, threads), expected);
+            }
+        }
+
+        let list = crate::tests::graph::undirected_list();
+        assert_eq!(
+            delta_stepping(&CsrGraph::try_from(&list).unwrap(), 0, 3, 2),
+            vec![
+                Some(0),
+                Some(3),
+                Some(1),
+                Some(8),
+                Some(4),
+                Some(6),
+                Some(7)
+            ]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::cmp::Ordering;
+
+use crate::{
+    binary_search_tree::{keyed_lookups, keyed_tree, Keyed},
+    binary_tree::{BinaryNode, BinaryTree, Link},
+};
+
+#[derive(Clone, Debug)]
+struct RbEntry<K, V> {
+    key: K,
+    value: V,
+    // colour of the link from the parent
+    red: bool,
+}
+
+impl<K: Ord, V> Keyed for RbEntry<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+type Node<K, V> = BinaryNode<RbEntry<K, V>>;
+type RbLink<K, V> = Link<RbEntry<K, V>>;
+
+// a left leaning red-black tree (Sedgewick): red links only ever lean left, so
+// every 2-3 tree shape has exactly one encoding and the fix ups stay short
+#[derive(Clone, Debug)]
+pub struct RedBlackTree<K, V> {
+    tree: BinaryTree<RbEntry<K, V>>,
+    len: usize,
+}
+
+fn is_red<K, V>(link: &RbLink<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| node.value.red)
+}
+
+fn is_left_red<K, V>(link: &RbLink<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| is_red(&node.left))
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    right.value.red = node.value.red;
+    node.value.red = true;
+    right.left = Some(node);
+    right
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    left.value.red = node.value.red;
+    node.value.red = true;
+    left.right = Some(node);
+    left
+}
+
+fn flip_colours<K, V>(node: &mut Node<K, V>) {
+    node.value.red = !node.value.red;
+    for child in [node.left.as_mut(), node.right.as_mut()]
+        .into_iter()
+        .flatten()
+    {
+        child.value.red = !child.value.red;
+    }
+}
+
+fn fix_up<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    if is_red(&node.right) && !is_red(&node.left) {
+        node = rotate_left(node);
+    }
+    if is_red(&node.left) && is_left_red(&node.left) {
+        node = rotate_right(node);
+    }
+    if is_red(&node.left) && is_red(&node.right) {
+        flip_colours(&mut node);
+    }
+    node
+}
+
+// borrow a red link so the left child is not a lone 2-node on the way down
+fn move_red_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    flip_colours(&mut node);
+    if is_left_red(&node.right) {
+        node.right = Some(rotate_right(node.right.take().unwrap()));
+        node = rotate_left(node);
+        flip_colours(&mut node);
+    }
+    node
+}
+
+fn move_red_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    flip_colours(&mut node);
+    if is_left_red(&node.left) {
+        node = rotate_right(node);
+        flip_colours(&mut node);
+    }
+    node
+}
+
+fn insert<K: Ord, V>(link: RbLink<K, V>, key: K, value: V) -> (Box<Node<K, V>>, Option<V>) {
+    let Some(mut node) = link else {
+        let entry = RbEntry {
+            key,
+            value,
+            red: true,
+        };
+        return (BinaryNode::boxed(entry, None, None), None);
+    };
+
+    let old = match key.cmp(&node.value.key) {
+        Ordering::Less => {
+            let (left, old) = insert(node.left.take(), key, value);
+            node.left = Some(left);
+            old
+        }
+        Ordering::Greater => {
+            let (right, old) = insert(node.right.take(), key, value);
+            node.right = Some(right);
+            old
+        }
+        Ordering::Equal => Some(std::mem::replace(&mut node.value.value, value)),
+    };
+
+    (fix_up(node), old)
+}
+
+fn remove_min<K, V>(mut node: Box<Node<K, V>>) -> (RbLink<K, V>, Box<Node<K, V>>) {
+    // with no left child a left leaning node has no right child either
+    if node.left.is_none() {
+        return (None, node);
+    }
+
+    if !is_red(&node.left) && !is_left_red(&node.left) {
+        node = move_red_left(node);
+    }
+    let (left, min) = remove_min(node.left.take().unwrap());
+    node.left = left;
+    (Some(fix_up(node)), min)
+}
+
+// only called with a key that is in the tree
+fn remove<K: Ord, V>(mut node: Box<Node<K, V>>, key: &K) -> (RbLink<K, V>, V) {
+    let old;
+
+    if *key < node.value.key {
+        if !is_red(&node.left) && !is_left_red(&node.left) {
+            node = move_red_left(node);
+        }
+        let (left, value) = remove(node.left.take().unwrap(), key);
+        node.left = left;
+        old = value;
+    } else {
+        if is_red(&node.left) {
+            node = rotate_right(node);
+        }
+        if *key == node.value.key && node.right.is_none() {
+            return (None, node.value.value);
+        }
+        if !is_red(&node.right) && !is_left_red(&node.right) {
+            node = move_red_right(node);
+        }
+
+        if *key == node.value.key {
+            // the successor's entry moves up into this node
+            let (right, min) = remove_min(node.right.take().unwrap());
+            node.right = right;
+            let min = min.value;
+            node.value.key = min.key;
+            old = std::mem::replace(&mut node.value.value, min.value);
+        } else {
+            let (right, value) = remove(node.right.take().unwrap(), key);
+            node.right = right;
+            old = value;
+        }
+    }
+
+    (Some(fix_up(node)), old)
+}
+
+// black height if ordering and the colour rules hold
+fn check<K: Ord, V>(link: &RbLink<K, V>, low: Option<&K>, high: Option<&K>) -> Option<usize> {
+    let Some(node) = link else {
+        return Some(1);
+    };
+
+    let key = &node.value.key;
+    if low.is_some_and(|low| key <= low) || high.is_some_and(|high| key >= high) {
+        return None;
+    }
+    // no red right links and no two reds in a row
+    if is_red(&node.right) || (node.value.red && is_red(&node.left)) {
+        return None;
+    }
+
+    let left = check(&node.left, low, Some(key))?;
+    let right = check(&node.right, Some(key), high)?;
+    (left == right).then_some(left + usize::from(!node.value.red))
+}
+
+fn height<K, V>(link: &RbLink<K, V>) -> usize {
+    link.as_ref()
+        .map_or(0, |node| 1 + height(&node.left).max(height(&node.right)))
+}
+
+impl<K: Ord, V> RedBlackTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn height(&self) -> usize {
+        height(&self.tree.head)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (mut head, old) = insert(self.tree.head.take(), key, value);
+        head.value.red = false;
+        self.tree.head = Some(head);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if !self.contains_key(key) {
+            return None;
+        }
+
+        let mut head = self.tree.head.take().unwrap();
+        if !is_red(&head.left) && !is_red(&head.right) {
+            head.value.red = true;
+        }
+
+        let (head, old) = remove(head, key);
+        self.tree.head = head;
+        if let Some(head) = self.tree.head.as_mut() {
+            head.value.red = false;
+        }
+        self.len -= 1;
+        Some(old)
+    }
+
+    // keys in order, a black root, no red right links or red pairs and the
+    // same number of black links on every path down
+    pub fn is_valid(&self) -> bool {
+        !is_red(&self.tree.head) && check(&self.tree.head, None, None).is_some()
+    }
+}
+
+keyed_lookups!(RedBlackTree);
+keyed_tree!(RedBlackTree);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::generators::Rng;
+
+    #[test]
+    fn test_sorted_inserts() {
+        let len = 100_000;
+        let mut tree: RedBlackTree<usize, usize> = (0..len).map(|key| (key, key)).collect();
+        assert!(tree.is_valid());
+        assert_eq!(tree.len(), len);
+        assert!(tree.height() as f64 <= 2.0 * (len as f64).log2());
+
+        for key in (0..len).rev().step_by(3) {
+            assert_eq!(tree.remove(&key), Some(key));
+        }
+        assert!(tree.is_valid());
+        assert_eq!(tree.len(), len - len.div_ceil(3));
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(45);
+        let mut tree = RedBlackTree::new();
+        let mut map = BTreeMap::new();
+
+        for _ in 0..4000 {
+            let key = rng.below(300);
+            if rng.chance(0.55) {
+                assert_eq!(tree.insert(key, key + 1), map.insert(key, key + 1));
+            } else {
+                assert_eq!(tree.remove(&key), map.remove(&key));
+            }
+
+            assert!(tree.is_valid());
+            assert_eq!(tree.len(), map.len());
+        }
+
+        assert!(tree.iter().eq(map.iter()));
+        assert!(tree.range(..100).eq(map.range(..100)));
+        assert!((0..300).all(|key| tree.get(&key) == map.get(&key)));
+
+        for key in 0..300 {
+            tree.remove(&key);
+            assert!(tree.is_valid());
+        }
+        assert!(tree.is_empty());
+    }
+}
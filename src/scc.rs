@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use crate::{
+    adjacency_list::{GraphEdge, WeightedAdjacencyList},
+    stack::Stack,
+};
+
+#[derive(Clone, Debug)]
+pub struct Components {
+    pub count: usize,
+    pub ids: Vec<usize>,
+}
+
+impl Components {
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = vec![vec![]; self.count];
+        for (v, &id) in self.ids.iter().enumerate() {
+            groups[id].push(v);
+        }
+        groups
+    }
+}
+
+pub fn tarjan(graph: &WeightedAdjacencyList) -> Components {
+    let len = graph.list.len();
+    let mut index = vec![None; len];
+    let mut low = vec![0; len];
+    let mut on_stack = vec![false; len];
+    let mut ids = vec![0; len];
+    let mut component = Stack::new();
+    let mut next_index = 0;
+    let mut count = 0;
+
+    for root in 0..len {
+        if index[root].is_some() {
+            continue;
+        }
+
+        let mut call = Stack::new();
+        call.push((root, 0));
+        index[root] = Some(next_index);
+        low[root] = next_index;
+        next_index += 1;
+        component.push(root);
+        on_stack[root] = true;
+
+        while let Some(top) = call.peek_mut() {
+            let (curr, i) = *top;
+
+            if i < graph.list[curr].len() {
+                top.1 += 1;
+                let next = graph.list[curr][i].to;
+
+                match index[next] {
+                    None => {
+                        index[next] = Some(next_index);
+                        low[next] = next_index;
+                        next_index += 1;
+                        component.push(next);
+                        on_stack[next] = true;
+                        call.push((next, 0));
+                    }
+                    Some(next_index) if on_stack[next] => {
+                        low[curr] = low[curr].min(next_index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            // every edge of curr is done, this is the "return" of the recursion
+            call.pop();
+            if let Some(&(parent, _)) = call.peek() {
+                low[parent] = low[parent].min(low[curr]);
+            }
+
+            if Some(low[curr]) == index[curr] {
+                while let Some(v) = component.pop() {
+                    on_stack[v] = false;
+                    ids[v] = count;
+                    if v == curr {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+    }
+
+    Components { count, ids }
+}
+
+fn transpose(graph: &WeightedAdjacencyList) -> WeightedAdjacencyList {
+    let mut list = vec![vec![]; graph.list.len()];
+    for (from, edges) in graph.list.iter().enumerate() {
+        for edge in edges.iter() {
+            list[edge.to].push(GraphEdge {
+                to: from,
+                weight: edge.weight,
+            });
+        }
+    }
+
+    WeightedAdjacencyList { list }
+}
+
+pub fn kosaraju(graph: &WeightedAdjacencyList) -> Components {
+    let len = graph.list.len();
+    let mut seen = vec![false; len];
+    let mut finished = vec![];
+
+    // 1. finishing order on the original graph
+    for root in 0..len {
+        if seen[root] {
+            continue;
+        }
+
+        let mut stack = Stack::new();
+        stack.push((root, 0));
+        seen[root] = true;
+
+        while let Some(top) = stack.peek_mut() {
+            let (curr, i) = *top;
+
+            if i == graph.list[curr].len() {
+                finished.push(curr);
+                stack.pop();
+                continue;
+            }
+
+            top.1 += 1;
+            let next = graph.list[curr][i].to;
+            if !seen[next] {
+                seen[next] = true;
+                stack.push((next, 0));
+            }
+        }
+    }
+
+    // 2. flood the transposed graph in reverse finishing order
+    let transposed = transpose(graph);
+    let mut ids = vec![None; len];
+    let mut count = 0;
+
+    for &root in finished.iter().rev() {
+        if ids[root].is_some() {
+            continue;
+        }
+
+        let mut stack = Stack::new();
+        stack.push(root);
+        ids[root] = Some(count);
+
+        while let Some(curr) = stack.pop() {
+            for edge in transposed.list[curr].iter() {
+                if ids[edge.to].is_none() {
+                    ids[edge.to] = Some(count);
+                    stack.push(edge.to);
+                }
+            }
+        }
+
+        count += 1;
+    }
+
+    Components {
+        count,
+        ids: ids.into_iter().map(|id| id.unwrap()).collect(),
+    }
+}
+
+pub fn condensation(
+    graph: &WeightedAdjacencyList,
+    components: &Components,
+) -> WeightedAdjacencyList {
+    let mut list: Vec<Vec<GraphEdge>> = vec![vec![]; components.count];
+    // where each neighbour already sits in list[from]
+    let mut index: Vec<HashMap<usize, usize>> = vec![HashMap::new(); components.count];
+
+    for (from, edges) in graph.list.iter().enumerate() {
+        let from = components.ids[from];
+
+        for edge in edges.iter() {
+            let to = components.ids[edge.to];
+            if from == to {
+                continue;
+            }
+
+            // parallel edges collapse into the cheapest one
+            match index[from].get(&to) {
+                Some(&i) => list[from][i].weight = list[from][i].weight.min(edge.weight),
+                None => {
+                    index[from].insert(to, list[from].len());
+                    list[from].push(GraphEdge {
+                        to,
+                        weight: edge.weight,
+                    });
+                }
+            }
+        }
+    }
+
+    WeightedAdjacencyList { list }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_groups(components: &Components) -> Vec<Vec<usize>> {
+        let mut groups = components.groups();
+        groups.sort();
+        groups
+    }
+
+    #[test]
+    fn test_scc() {
+        let graph = crate::tests::graph::directed_list();
+        let expected = vec![vec![0], vec![1, 4], vec![2], vec![3], vec![5], vec![6]];
+
+        let components = tarjan(&graph);
+        assert_eq!(components.count, 6);
+        assert_eq!(sorted_groups(&components), expected);
+
+        let components = kosaraju(&graph);
+        assert_eq!(components.count, 6);
+        assert_eq!(sorted_groups(&components), expected);
+
+        let graph = crate::tests::graph::undirected_list();
+        assert_eq!(tarjan(&graph).count, 1);
+        assert_eq!(kosaraju(&graph).count, 1);
+    }
+
+    #[test]
+    fn test_condensation() {
+        let graph = crate::tests::graph::directed_list();
+        let components = kosaraju(&graph);
+        let dag = condensation(&graph, &components);
+
+        assert_eq!(dag.list.len(), 6);
+        assert!(crate::dag::kahn(&dag).is_ok());
+
+        let id = |v: usize| components.ids[v];
+        let edges: Vec<(usize, usize, usize)> = dag
+            .list
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge.to, edge.weight)))
+            .collect();
+
+        // 4 -> 1 and 1 -> 4 vanish inside the component, 0 -> 1 survives
+        assert!(edges.contains(&(id(0), id(1), 3)));
+        assert!(edges.contains(&(id(4), id(3), 5)));
+        assert!(!edges.iter().any(|&(from, to, _)| from == to));
+        assert_eq!(edges.len(), 8);
+    }
+
+    #[test]
+    fn test_scc_long_path() {
+        let len = 1_000_000;
+        let mut list: Vec<Vec<GraphEdge>> = (1..len)
+            .map(|to| vec![GraphEdge { to, weight: 1 }])
+            .collect();
+        list.push(vec![]);
+        let mut graph = WeightedAdjacencyList { list };
+
+        assert_eq!(tarjan(&graph).count, len);
+        assert_eq!(kosaraju(&graph).count, len);
+
+        graph.list[len - 1].push(GraphEdge { to: 0, weight: 1 });
+        assert_eq!(tarjan(&graph).count, 1);
+        assert_eq!(kosaraju(&graph).count, 1);
+    }
+
+    #[test]
+    fn test_condensation_hub() {
+        // a hub reaching every other vertex twice; scanning the hub's edges
+        // for each new one would be quadratic here
+        let len = 200_000;
+        let hub: Vec<GraphEdge> = (1..len)
+            .flat_map(|to| [GraphEdge { to, weight: 2 }, GraphEdge { to, weight: 1 }])
+            .collect();
+        let mut list = vec![vec![]; len];
+        list[0] = hub;
+        let graph = WeightedAdjacencyList { list };
+
+        let components = tarjan(&graph);
+        let dag = condensation(&graph, &components);
+        let hub = &dag.list[components.ids[0]];
+        assert_eq!(hub.len(), len - 1);
+        assert!(hub.iter().all(|edge| edge.weight == 1));
+    }
+}
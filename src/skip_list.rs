@@ -0,0 +1,304 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::generators::Rng;
+
+const MAX_LEVEL: usize = 32;
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct SkipNode<K, V> {
+    key: K,
+    value: V,
+    // the following node on each level this one is part of
+    next: Vec<usize>,
+}
+
+// sorted linked lists stacked on top of each other, each level holding every
+// node of the one below with probability 1/2. nodes sit in an arena and the
+// head of every level is kept apart, so there is no sentinel key
+#[derive(Clone, Debug)]
+pub struct SkipList<K, V> {
+    nodes: Vec<Option<SkipNode<K, V>>>,
+    free: Vec<usize>,
+    head: Vec<usize>,
+    len: usize,
+    rng: Rng,
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    pub fn new() -> Self {
+        Self::with_seed(0x736b_6970)
+    }
+
+    // the seed fixes the level of every inserted node, and so the shape
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            nodes: vec![],
+            free: vec![],
+            head: vec![],
+            len: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn levels(&self) -> usize {
+        self.head.len()
+    }
+
+    fn node(&self, id: usize) -> &SkipNode<K, V> {
+        self.nodes[id].as_ref().unwrap()
+    }
+
+    // None stands for the head
+    fn next(&self, at: Option<usize>, level: usize) -> usize {
+        match at {
+            Some(id) => self.node(id).next[level],
+            None => self.head[level],
+        }
+    }
+
+    fn set_next(&mut self, at: Option<usize>, level: usize, to: usize) {
+        match at {
+            Some(id) => self.nodes[id].as_mut().unwrap().next[level] = to,
+            None => self.head[level] = to,
+        }
+    }
+
+    // the last node before the key on every level, from the bottom up
+    fn predecessors(&self, key: &K) -> Vec<Option<usize>> {
+        let mut out = vec![None; self.head.len()];
+        let mut at = None;
+        for level in (0..self.head.len()).rev() {
+            loop {
+                let next = self.next(at, level);
+                if next == NIL || self.node(next).key >= *key {
+                    break;
+                }
+                at = Some(next);
+            }
+            out[level] = at;
+        }
+        out
+    }
+
+    // the node holding the key, if any
+    fn find(&self, key: &K) -> Option<usize> {
+        let at = *self.predecessors(key).first()?;
+        let next = self.next(at, 0);
+        (next != NIL && self.node(next).key == *key).then_some(next)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|id| &self.node(id).value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let id = self.find(key)?;
+        Some(&mut self.nodes[id].as_mut().unwrap().value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    // geometric with p = 1/2, one coin per random bit
+    fn random_level(&mut self) -> usize {
+        (self.rng.next_u64().trailing_ones() as usize + 1).min(MAX_LEVEL)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        let mut preds = self.predecessors(&key);
+        let level = self.random_level();
+        while self.head.len() < level {
+            self.head.push(NIL);
+            preds.push(None);
+        }
+
+        let next = (0..level).map(|l| self.next(preds[l], l)).collect();
+        let node = Some(SkipNode { key, value, next });
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        for (l, &pred) in preds.iter().enumerate().take(level) {
+            self.set_next(pred, l, id);
+        }
+        self.len += 1;
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let id = self.find(key)?;
+        let preds = self.predecessors(key);
+        let node = self.nodes[id].take().unwrap();
+        self.free.push(id);
+
+        for (l, &next) in node.next.iter().enumerate() {
+            self.set_next(preds[l], l, next);
+        }
+        while self.head.last() == Some(&NIL) {
+            self.head.pop();
+        }
+
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.range(..)
+    }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, &V)> {
+        let first = match range.start_bound() {
+            Bound::Included(start) => self.predecessors(start).first().map(|&at| self.next(at, 0)),
+            Bound::Excluded(start) => self.predecessors(start).first().map(|&at| {
+                let next = self.next(at, 0);
+                match next != NIL && self.node(next).key == *start {
+                    true => self.node(next).next[0],
+                    false => next,
+                }
+            }),
+            Bound::Unbounded => self.head.first().copied(),
+        };
+
+        let mut curr = first.unwrap_or(NIL);
+        std::iter::from_fn(move || {
+            if curr == NIL {
+                return None;
+            }
+            let node = self.node(curr);
+            curr = node.next[0];
+            Some((&node.key, &node.value))
+        })
+        .take_while(move |(key, _)| match range.end_bound() {
+            Bound::Included(end) => *key <= end,
+            Bound::Excluded(end) => *key < end,
+            Bound::Unbounded => true,
+        })
+    }
+
+    // every level sorted, each node on a level also on all levels below it,
+    // no empty levels on top and the bottom level as long as the list
+    pub fn is_valid(&self) -> bool {
+        if self.head.last() == Some(&NIL) {
+            return false;
+        }
+
+        for level in 0..self.head.len() {
+            let mut at = None;
+            loop {
+                let next = self.next(at, level);
+                if next == NIL {
+                    break;
+                }
+                let node = self.node(next);
+                let ordered = at.is_none_or(|at| self.node(at).key < node.key);
+                if !ordered || node.next.len() <= level {
+                    return false;
+                }
+                at = Some(next);
+            }
+        }
+
+        self.iter().count() == self.len
+    }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipList<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut list = Self::new();
+        for (key, value) in iter {
+            list.insert(key, value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_seeded_levels() {
+        let mut a = SkipList::with_seed(9);
+        let mut b = SkipList::with_seed(9);
+        for key in 0..1000 {
+            a.insert(key, ());
+            b.insert(key, ());
+        }
+
+        let shape = |list: &SkipList<i32, ()>| -> Vec<usize> {
+            list.nodes
+                .iter()
+                .flatten()
+                .map(|node| node.next.len())
+                .collect()
+        };
+        assert_eq!(shape(&a), shape(&b));
+
+        // about half the nodes reach each next level up
+        let high = shape(&a).iter().filter(|&&level| level >= 4).count();
+        assert!((80..170).contains(&high));
+        assert!(a.levels() <= 20);
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(47);
+        let mut list = SkipList::with_seed(2);
+        let mut map = BTreeMap::new();
+
+        for _ in 0..4000 {
+            let key = rng.below(300);
+            if rng.chance(0.55) {
+                assert_eq!(list.insert(key, key + 1), map.insert(key, key + 1));
+            } else {
+                assert_eq!(list.remove(&key), map.remove(&key));
+            }
+
+            assert!(list.is_valid());
+            assert_eq!(list.len(), map.len());
+        }
+
+        assert!(list.iter().eq(map.iter()));
+        assert!((0..300).all(|key| list.get(&key) == map.get(&key)));
+        for (low, high) in [(0, 10), (17, 17), (100, 299), (250, 400)] {
+            assert!(list.range(low..high).eq(map.range(low..high)));
+            let bounds = (Bound::Excluded(low), Bound::Included(high));
+            assert!(list.range(bounds).eq(map.range(bounds)));
+        }
+
+        for key in 0..300 {
+            list.remove(&key);
+        }
+        assert!(list.is_empty() && list.is_valid());
+        assert_eq!(list.levels(), 0);
+    }
+}
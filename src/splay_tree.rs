@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+
+use crate::{
+    binary_search_tree::{find, keyed_tree, Keyed},
+    binary_tree::{BinaryNode, BinaryTree},
+};
+
+#[derive(Clone, Debug)]
+struct SplayEntry<K, V> {
+    key: K,
+    value: V,
+}
+
+impl<K: Ord, V> Keyed for SplayEntry<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+type Node<K, V> = BinaryNode<SplayEntry<K, V>>;
+
+// every access rotates the node it found (or the last one it passed) to the
+// root, so recently used keys stay near the top. there is no balance to rely
+// on, sorted inserts leave a path n nodes long, so nothing here recurses
+#[derive(Clone, Debug)]
+pub struct SplayTree<K, V> {
+    tree: BinaryTree<SplayEntry<K, V>>,
+    len: usize,
+}
+
+// top down splay (Sleator and Tarjan): nodes smaller and larger than the key
+// are cut off on the way down and hung back on either side of the new root
+fn splay<K: Ord, V>(mut root: Box<Node<K, V>>, key: &K) -> Box<Node<K, V>> {
+    let mut smaller = vec![];
+    let mut larger = vec![];
+
+    loop {
+        match key.cmp(&root.value.key) {
+            Ordering::Less => {
+                let Some(mut child) = root.left.take() else {
+                    break;
+                };
+                if *key < child.value.key {
+                    // zig-zig, rotate right before linking
+                    root.left = child.right.take();
+                    child.right = Some(root);
+                    root = child;
+                    child = match root.left.take() {
+                        Some(child) => child,
+                        None => break,
+                    };
+                }
+                larger.push(root);
+                root = child;
+            }
+            Ordering::Greater => {
+                let Some(mut child) = root.right.take() else {
+                    break;
+                };
+                if *key > child.value.key {
+                    root.right = child.left.take();
+                    child.left = Some(root);
+                    root = child;
+                    child = match root.right.take() {
+                        Some(child) => child,
+                        None => break,
+                    };
+                }
+                smaller.push(root);
+                root = child;
+            }
+            Ordering::Equal => break,
+        }
+    }
+
+    // each cut off node hangs below the one cut before it on the same side
+    let mut left = root.left.take();
+    for mut node in smaller.into_iter().rev() {
+        node.right = left;
+        left = Some(node);
+    }
+    let mut right = root.right.take();
+    for mut node in larger.into_iter().rev() {
+        node.left = right;
+        right = Some(node);
+    }
+
+    root.left = left;
+    root.right = right;
+    root
+}
+
+impl<K: Ord, V> SplayTree<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack = vec![(&self.tree.head, 0)];
+        while let Some((link, depth)) = stack.pop() {
+            if let Some(node) = link {
+                height = height.max(depth + 1);
+                stack.push((&node.left, depth + 1));
+                stack.push((&node.right, depth + 1));
+            }
+        }
+        height
+    }
+
+    // moves the key, or its neighbour if it is missing, to the root and
+    // reports whether it was found
+    fn splay(&mut self, key: &K) -> bool {
+        match self.tree.head.take() {
+            Some(head) => {
+                let head = splay(head, key);
+                let found = head.value.key == *key;
+                self.tree.head = Some(head);
+                found
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match self.splay(key) {
+            true => self.tree.head.as_mut().map(|head| &mut head.value.value),
+            false => None,
+        }
+    }
+
+    pub fn contains_key(&mut self, key: &K) -> bool {
+        self.splay(key)
+    }
+
+    // a lookup that leaves the shape alone
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        find(&self.tree.head, key).map(|node| &node.value.value)
+    }
+
+    pub fn root_key(&self) -> Option<&K> {
+        self.tree.head.as_ref().map(|head| &head.value.key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.splay(&key) {
+            let head = self.tree.head.as_mut().unwrap();
+            return Some(std::mem::replace(&mut head.value.value, value));
+        }
+
+        // the old root is the new key's neighbour, so it and one of its
+        // subtrees go on one side and the other subtree on the other
+        let entry = SplayEntry { key, value };
+        let head = match self.tree.head.take() {
+            None => BinaryNode::boxed(entry, None, None),
+            Some(mut head) if entry.key < head.value.key => {
+                let left = head.left.take();
+                BinaryNode::boxed(entry, left, Some(head))
+            }
+            Some(mut head) => {
+                let right = head.right.take();
+                BinaryNode::boxed(entry, Some(head), right)
+            }
+        };
+
+        self.tree.head = Some(head);
+        self.len += 1;
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        if !self.splay(key) {
+            return None;
+        }
+
+        let head = *self.tree.head.take().unwrap();
+        // splaying the left side for the same key lifts its maximum, which
+        // then has no right child and can take the whole right side
+        self.tree.head = match head.left {
+            None => head.right,
+            Some(left) => {
+                let mut left = splay(left, key);
+                left.right = head.right;
+                Some(left)
+            }
+        };
+
+        self.len -= 1;
+        Some(head.value.value)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let ordered = self
+            .iter()
+            .zip(self.iter().skip(1))
+            .all(|((a, _), (b, _))| a < b);
+        ordered && self.iter().count() == self.len
+    }
+}
+
+keyed_tree!(SplayTree);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::generators::Rng;
+
+    #[test]
+    fn test_splay_to_root() {
+        let mut tree: SplayTree<i32, i32> = (0..10).map(|key| (key, key * key)).collect();
+        assert_eq!(tree.root_key(), Some(&9));
+
+        assert_eq!(tree.get(&4), Some(&16));
+        assert_eq!(tree.root_key(), Some(&4));
+        assert_eq!(tree.peek(&7), Some(&49));
+        assert_eq!(tree.root_key(), Some(&4));
+
+        // a miss still brings a neighbour up
+        assert!(!tree.contains_key(&20));
+        assert_eq!(tree.root_key(), Some(&9));
+        assert!(tree.is_valid());
+    }
+
+    #[test]
+    fn test_sorted_inserts() {
+        // a path as long as the tree, then a walk down it halves the depth
+        let len = 100_000;
+        let mut tree: SplayTree<usize, usize> = (0..len).map(|key| (key, key)).collect();
+        assert_eq!(tree.height(), len);
+
+        assert_eq!(tree.get(&0), Some(&0));
+        assert!(tree.height() <= len / 2 + 2);
+        for key in 0..len {
+            assert_eq!(tree.get(&key), Some(&key));
+        }
+        assert!(tree.is_valid());
+
+        for key in (0..len).rev() {
+            assert_eq!(tree.remove(&key), Some(key));
+        }
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_clone_deep_tree() {
+        // sorted inserts leave one long path, which clone must not recurse down
+        let len = 300_000;
+        let tree: SplayTree<usize, usize> = (0..len).map(|key| (key, key)).collect();
+        let copy = tree.clone();
+        assert_eq!(copy.height(), len);
+        assert!(copy.iter().eq(tree.iter()));
+        assert!(format!("{:?}", copy).len() > len);
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(47);
+        let mut tree = SplayTree::new();
+        let mut map = BTreeMap::new();
+
+        for _ in 0..4000 {
+            let key = rng.below(300);
+            match rng.below(3) {
+                0 => assert_eq!(tree.insert(key, key + 1), map.insert(key, key + 1)),
+                1 => assert_eq!(tree.remove(&key), map.remove(&key)),
+                _ => assert_eq!(tree.get(&key), map.get(&key)),
+            }
+            assert_eq!(tree.len(), map.len());
+        }
+
+        assert!(tree.is_valid());
+        assert!(tree.iter().eq(map.iter()));
+        assert!(tree.range(40..=90).eq(map.range(40..=90)));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::adjacency_list::{GraphEdge, WeightedAdjacencyList};
+
+pub fn directed_list() -> WeightedAdjacencyList {
+    //     >(1)<--->(4) ---->(5)
+    //    /          |       /|
+    // (0)     ------|------- |
+    //    \   v      v        v
+    //     >(2) --> (3) <----(6)
+    WeightedAdjacencyList {
+        list: vec![
+            vec![
+                GraphEdge { to: 1, weight: 3 },
+                GraphEdge { to: 2, weight: 1 },
+            ],
+            vec![GraphEdge { to: 4, weight: 1 }],
+            vec![GraphEdge { to: 3, weight: 7 }],
+            vec![],
+            vec![
+                GraphEdge { to: 1, weight: 1 },
+                GraphEdge { to: 3, weight: 5 },
+                GraphEdge { to: 5, weight: 2 },
+            ],
+            vec![
+                GraphEdge { to: 2, weight: 18 },
+                GraphEdge { to: 6, weight: 1 },
+            ],
+            vec![GraphEdge { to: 3, weight: 1 }],
+        ],
+    }
+}
+
+pub fn undirected_list() -> WeightedAdjacencyList {
+    //      (1) --- (4) ---- (5)
+    //    /  |       |       /|
+    // (0)   | ------|------- |
+    //    \  |/      |        |
+    //      (2) --- (3) ---- (6)
+    WeightedAdjacencyList {
+        list: vec![
+            vec![
+                GraphEdge { to: 1, weight: 3 },
+                GraphEdge { to: 2, weight: 1 },
+            ],
+            vec![
+                GraphEdge { to: 0, weight: 3 },
+                GraphEdge { to: 2, weight: 4 },
+                GraphEdge { to: 4, weight: 1 },
+            ],
+            vec![
+                GraphEdge { to: 1, weight: 4 },
+                GraphEdge { to: 3, weight: 7 },
+                GraphEdge { to: 0, weight: 1 },
+            ],
+            vec![
+                GraphEdge { to: 2, weight: 7 },
+                GraphEdge { to: 4, weight: 5 },
+                GraphEdge { to: 6, weight: 1 },
+            ],
+            vec![
+                GraphEdge { to: 1, weight: 1 },
+                GraphEdge { to: 3, weight: 5 },
+                GraphEdge { to: 5, weight: 2 },
+            ],
+            vec![
+                GraphEdge { to: 6, weight: 1 },
+                GraphEdge { to: 4, weight: 2 },
+                GraphEdge { to: 2, weight: 18 },
+            ],
+            vec![
+                GraphEdge { to: 3, weight: 1 },
+                GraphEdge { to: 5, weight: 1 },
+            ],
+        ],
+    }
+}
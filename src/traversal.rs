@@ -0,0 +1,419 @@
+use crate::{
+    adjacency_list::WeightedAdjacencyList, adjacency_matrix::WeightedAdjacencyMatrix, queue::Queue,
+    stack::Stack,
+};
+
+pub trait Neighbours {
+    fn vertex_count(&self) -> usize;
+
+    // the first neighbour at or after `cursor`, together with the cursor to
+    // continue from
+    fn next_neighbour(&self, vertex: usize, cursor: usize) -> Option<(usize, usize)>;
+}
+
+impl Neighbours for WeightedAdjacencyList {
+    fn vertex_count(&self) -> usize {
+        self.list.len()
+    }
+
+    fn next_neighbour(&self, vertex: usize, cursor: usize) -> Option<(usize, usize)> {
+        self.list[vertex]
+            .get(cursor)
+            .map(|edge| (edge.to, cursor + 1))
+    }
+}
+
+impl Neighbours for WeightedAdjacencyMatrix {
+    fn vertex_count(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn next_neighbour(&self, vertex: usize, cursor: usize) -> Option<(usize, usize)> {
+        (cursor..self.matrix[vertex].len())
+            .find(|&to| self.matrix[vertex][to].is_some())
+            .map(|to| (to, to + 1))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisitEvent {
+    Discover { vertex: usize, depth: usize },
+    Finish { vertex: usize, depth: usize },
+    TreeEdge { from: usize, to: usize },
+    BackEdge { from: usize, to: usize },
+    ForwardEdge { from: usize, to: usize },
+    CrossEdge { from: usize, to: usize },
+}
+
+pub struct Bfs<'a, G: Neighbours> {
+    graph: &'a G,
+    queue: Queue<usize>,
+    depth: Vec<Option<usize>>,
+    parent: Vec<Option<usize>>,
+    curr: Option<(usize, usize)>,
+    pending: Option<VisitEvent>,
+}
+
+impl<'a, G: Neighbours> Bfs<'a, G> {
+    pub fn new(graph: &'a G, source: usize) -> Self {
+        let mut depth = vec![None; graph.vertex_count()];
+        depth[source] = Some(0);
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+
+        Self {
+            graph,
+            queue,
+            depth,
+            parent: vec![None; graph.vertex_count()],
+            curr: None,
+            pending: Some(VisitEvent::Discover {
+                vertex: source,
+                depth: 0,
+            }),
+        }
+    }
+
+    pub fn depth(&self, vertex: usize) -> Option<usize> {
+        self.depth[vertex]
+    }
+
+    // walks up the tree from vertex, which counts as its own ancestor
+    fn is_ancestor(&self, ancestor: usize, vertex: usize) -> bool {
+        let mut curr = Some(vertex);
+        while let Some(v) = curr {
+            if v == ancestor {
+                return true;
+            }
+            curr = self.parent[v];
+        }
+        false
+    }
+}
+
+impl<G: Neighbours> Iterator for Bfs<'_, G> {
+    type Item = VisitEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.take() {
+            return Some(event);
+        }
+
+        let (from, cursor) = match self.curr {
+            Some(curr) => curr,
+            None => (self.queue.deque()?, 0),
+        };
+        let depth = self.depth[from].unwrap();
+
+        match self.graph.next_neighbour(from, cursor) {
+            Some((to, cursor)) => {
+                self.curr = Some((from, cursor));
+
+                if self.depth[to].is_some() {
+                    // a queue does not tell ancestors apart from other seen
+                    // vertices, so the tree is walked up for each of these;
+                    // a forward edge can only repeat a tree edge here
+                    return Some(if self.is_ancestor(to, from) {
+                        VisitEvent::BackEdge { from, to }
+                    } else if self.is_ancestor(from, to) {
+                        VisitEvent::ForwardEdge { from, to }
+                    } else {
+                        VisitEvent::CrossEdge { from, to }
+                    });
+                }
+
+                self.depth[to] = Some(depth + 1);
+                self.parent[to] = Some(from);
+                self.queue.enqueue(to);
+                self.pending = Some(VisitEvent::Discover {
+                    vertex: to,
+                    depth: depth + 1,
+                });
+                Some(VisitEvent::TreeEdge { from, to })
+            }
+            None => {
+                self.curr = None;
+                Some(VisitEvent::Finish {
+                    vertex: from,
+                    depth,
+                })
+            }
+        }
+    }
+}
+
+pub struct Dfs<'a, G: Neighbours> {
+    graph: &'a G,
+    stack: Stack<(usize, usize)>,
+    depth: usize,
+    time: usize,
+    discovered: Vec<Option<usize>>,
+    finished: Vec<bool>,
+    pending: Option<VisitEvent>,
+}
+
+impl<'a, G: Neighbours> Dfs<'a, G> {
+    pub fn new(graph: &'a G, source: usize) -> Self {
+        let mut discovered = vec![None; graph.vertex_count()];
+        discovered[source] = Some(0);
+        let mut stack = Stack::new();
+        stack.push((source, 0));
+
+        Self {
+            graph,
+            stack,
+            depth: 0,
+            time: 1,
+            discovered,
+            finished: vec![false; graph.vertex_count()],
+            pending: Some(VisitEvent::Discover {
+                vertex: source,
+                depth: 0,
+            }),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<G: Neighbours> Iterator for Dfs<'_, G> {
+    type Item = VisitEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.take() {
+            return Some(event);
+        }
+
+        let top = self.stack.peek_mut()?;
+        let (from, cursor) = *top;
+
+        match self.graph.next_neighbour(from, cursor) {
+            Some((to, cursor)) => {
+                top.1 = cursor;
+
+                match self.discovered[to] {
+                    None => {
+                        self.discovered[to] = Some(self.time);
+                        self.time += 1;
+                        self.depth += 1;
+                        self.stack.push((to, 0));
+                        self.pending = Some(VisitEvent::Discover {
+                            vertex: to,
+                            depth: self.depth,
+                        });
+                        Some(VisitEvent::TreeEdge { from, to })
+                    }
+                    Some(_) if !self.finished[to] => Some(VisitEvent::BackEdge { from, to }),
+                    Some(time) if time > self.discovered[from].unwrap() => {
+                        Some(VisitEvent::ForwardEdge { from, to })
+                    }
+                    Some(_) => Some(VisitEvent::CrossEdge { from, to }),
+                }
+            }
+            None => {
+                self.stack.pop();
+                self.finished[from] = true;
+                let depth = self.depth;
+                self.depth = self.depth.saturating_sub(1);
+                Some(VisitEvent::Finish {
+                    vertex: from,
+                    depth,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjacency_list::GraphEdge;
+
+    fn discovered(events: impl Iterator<Item = VisitEvent>) -> Vec<usize> {
+        events
+            .filter_map(|event| match event {
+                VisitEvent::Discover { vertex, .. } => Some(vertex),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let graph = WeightedAdjacencyMatrix {
+            matrix: vec![
+                vec![None, Some(1), Some(2), None],
+                vec![None, None, Some(3), None],
+                vec![None, None, None, Some(1)],
+                vec![Some(1), None, None, None],
+            ],
+        };
+
+        let events: Vec<VisitEvent> = Bfs::new(&graph, 0).collect();
+        assert_eq!(
+            events,
+            vec![
+                VisitEvent::Discover {
+                    vertex: 0,
+                    depth: 0
+                },
+                VisitEvent::TreeEdge { from: 0, to: 1 },
+                VisitEvent::Discover {
+                    vertex: 1,
+                    depth: 1
+                },
+                VisitEvent::TreeEdge { from: 0, to: 2 },
+                VisitEvent::Discover {
+                    vertex: 2,
+                    depth: 1
+                },
+                VisitEvent::Finish {
+                    vertex: 0,
+                    depth: 0
+                },
+                VisitEvent::CrossEdge { from: 1, to: 2 },
+                VisitEvent::Finish {
+                    vertex: 1,
+                    depth: 1
+                },
+                VisitEvent::TreeEdge { from: 2, to: 3 },
+                VisitEvent::Discover {
+                    vertex: 3,
+                    depth: 2
+                },
+                VisitEvent::Finish {
+                    vertex: 2,
+                    depth: 1
+                },
+                VisitEvent::BackEdge { from: 3, to: 0 },
+                VisitEvent::Finish {
+                    vertex: 3,
+                    depth: 2
+                },
+            ]
+        );
+
+        let graph = crate::tests::graph::directed_list();
+        assert_eq!(discovered(Bfs::new(&graph, 0)), vec![0, 1, 2, 4, 3, 5, 6]);
+
+        // stop as soon as 4 turns up
+        let mut bfs = Bfs::new(&graph, 0);
+        assert!(bfs.by_ref().any(|event| event
+            == VisitEvent::Discover {
+                vertex: 4,
+                depth: 2
+            }));
+        assert_eq!(bfs.depth(4), Some(2));
+        assert_eq!(bfs.depth(5), None);
+    }
+
+    #[test]
+    fn test_bfs_edge_kinds() {
+        // (0) ==> (1) --> (2) -> itself, 2 -> 0 and 1 -> 0 closing cycles
+        let graph = WeightedAdjacencyList {
+            list: vec![
+                vec![
+                    GraphEdge { to: 1, weight: 1 },
+                    GraphEdge { to: 1, weight: 1 },
+                ],
+                vec![
+                    GraphEdge { to: 2, weight: 1 },
+                    GraphEdge { to: 0, weight: 1 },
+                ],
+                vec![
+                    GraphEdge { to: 2, weight: 1 },
+                    GraphEdge { to: 0, weight: 1 },
+                ],
+            ],
+        };
+        let edges: Vec<_> = Bfs::new(&graph, 0)
+            .filter(|event| {
+                !matches!(
+                    event,
+                    VisitEvent::Discover { .. } | VisitEvent::Finish { .. }
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            edges,
+            vec![
+                VisitEvent::TreeEdge { from: 0, to: 1 },
+                VisitEvent::ForwardEdge { from: 0, to: 1 },
+                VisitEvent::TreeEdge { from: 1, to: 2 },
+                VisitEvent::BackEdge { from: 1, to: 0 },
+                VisitEvent::BackEdge { from: 2, to: 2 },
+                VisitEvent::BackEdge { from: 2, to: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dfs() {
+        // (0) --> (1) --> (2)
+        //   \      ^      /
+        //    \     |     v
+        //     ---> (3) <-
+        let graph = WeightedAdjacencyList {
+            list: vec![
+                vec![
+                    GraphEdge { to: 1, weight: 1 },
+                    GraphEdge { to: 3, weight: 1 },
+                ],
+                vec![GraphEdge { to: 2, weight: 1 }],
+                vec![GraphEdge { to: 3, weight: 1 }],
+                vec![GraphEdge { to: 1, weight: 1 }],
+            ],
+        };
+
+        let edges: Vec<VisitEvent> = Dfs::new(&graph, 0)
+            .filter(|event| {
+                !matches!(
+                    event,
+                    VisitEvent::Discover { .. } | VisitEvent::Finish { .. }
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                VisitEvent::TreeEdge { from: 0, to: 1 },
+                VisitEvent::TreeEdge { from: 1, to: 2 },
+                VisitEvent::TreeEdge { from: 2, to: 3 },
+                VisitEvent::BackEdge { from: 3, to: 1 },
+                VisitEvent::ForwardEdge { from: 0, to: 3 },
+            ]
+        );
+
+        // (0) --> (1)
+        //   \      ^
+        //    --> (2)
+        let graph = WeightedAdjacencyList {
+            list: vec![
+                vec![
+                    GraphEdge { to: 1, weight: 1 },
+                    GraphEdge { to: 2, weight: 1 },
+                ],
+                vec![],
+                vec![GraphEdge { to: 1, weight: 1 }],
+            ],
+        };
+        let events: Vec<VisitEvent> = Dfs::new(&graph, 0).collect();
+        assert!(events.contains(&VisitEvent::CrossEdge { from: 2, to: 1 }));
+        assert!(events.contains(&VisitEvent::Finish {
+            vertex: 1,
+            depth: 1
+        }));
+
+        let graph = crate::tests::graph::directed_list();
+        assert_eq!(discovered(Dfs::new(&graph, 0)), vec![0, 1, 4, 3, 5, 2, 6]);
+
+        let mut dfs = Dfs::new(&graph, 0);
+        assert!(dfs
+            .by_ref()
+            .any(|event| matches!(event, VisitEvent::Discover { vertex: 5, .. })));
+        assert_eq!(dfs.depth(), 3);
+    }
+}
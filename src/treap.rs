@@ -0,0 +1,323 @@
+use std::cmp::Ordering;
+
+use crate::{
+    binary_search_tree::{keyed_lookups, keyed_tree, Keyed},
+    binary_tree::{BinaryNode, BinaryTree, Link},
+    generators::Rng,
+};
+
+#[derive(Clone, Debug)]
+struct TreapEntry<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    // nodes in this subtree, so splits know how big each half is
+    size: usize,
+}
+
+impl<K: Ord, V> Keyed for TreapEntry<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn key(&self) -> &K {
+        &self.key
+    }
+
+    fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+type Node<K, V> = BinaryNode<TreapEntry<K, V>>;
+type TreapLink<K, V> = Link<TreapEntry<K, V>>;
+
+// a search tree on the keys and a max heap on random priorities at once,
+// which makes its shape that of a tree built from a random insertion order;
+// everything is split and merge, so the expected depth of O(log n) is also
+// the recursion depth
+#[derive(Clone, Debug)]
+pub struct Treap<K, V> {
+    tree: BinaryTree<TreapEntry<K, V>>,
+    rng: Rng,
+}
+
+fn size<K, V>(link: &TreapLink<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.value.size)
+}
+
+fn update<K, V>(node: &mut Node<K, V>) {
+    node.value.size = 1 + size(&node.left) + size(&node.right);
+}
+
+// keys below the split key go left, the rest right
+fn split<K: Ord, V>(link: TreapLink<K, V>, key: &K) -> (TreapLink<K, V>, TreapLink<K, V>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+
+    if node.value.key < *key {
+        let (left, right) = split(node.right.take(), key);
+        node.right = left;
+        update(&mut node);
+        (Some(node), right)
+    } else {
+        let (left, right) = split(node.left.take(), key);
+        node.left = right;
+        update(&mut node);
+        (left, Some(node))
+    }
+}
+
+// every key on the left must be below every key on the right
+fn merge<K, V>(left: TreapLink<K, V>, right: TreapLink<K, V>) -> TreapLink<K, V> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.value.priority > right.value.priority {
+                left.right = merge(left.right.take(), Some(right));
+                update(&mut left);
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                update(&mut right);
+                Some(right)
+            }
+        }
+    }
+}
+
+fn remove<K: Ord, V>(link: &mut TreapLink<K, V>, key: &K) -> Option<V> {
+    let node = link.as_mut()?;
+    let old = match key.cmp(&node.value.key) {
+        Ordering::Less => remove(&mut node.left, key),
+        Ordering::Greater => remove(&mut node.right, key),
+        Ordering::Equal => {
+            let node = *link.take().unwrap();
+            *link = merge(node.left, node.right);
+            return Some(node.value.value);
+        }
+    };
+
+    if old.is_some() {
+        update(node);
+    }
+    old
+}
+
+// subtree size if keys are ordered, priorities form a heap and sizes add up
+fn check<K: Ord, V>(
+    link: &TreapLink<K, V>,
+    low: Option<&K>,
+    high: Option<&K>,
+    priority: u64,
+) -> Option<usize> {
+    let Some(node) = link else {
+        return Some(0);
+    };
+
+    let key = &node.value.key;
+    if low.is_some_and(|low| key <= low)
+        || high.is_some_and(|high| key >= high)
+        || node.value.priority > priority
+    {
+        return None;
+    }
+
+    let left = check(&node.left, low, Some(key), node.value.priority)?;
+    let right = check(&node.right, Some(key), high, node.value.priority)?;
+    let size = 1 + left + right;
+    (node.value.size == size).then_some(size)
+}
+
+// the largest key at the end of the right spine or the smallest at the end
+// of the left one, O(log n) steps down either way
+fn spine_end<K, V>(link: &TreapLink<K, V>, right: bool) -> Option<&K> {
+    let mut node = link.as_deref()?;
+    while let Some(next) = match right {
+        true => node.right.as_deref(),
+        false => node.left.as_deref(),
+    } {
+        node = next;
+    }
+    Some(&node.value.key)
+}
+
+fn height<K, V>(link: &TreapLink<K, V>) -> usize {
+    link.as_ref()
+        .map_or(0, |node| 1 + height(&node.left).max(height(&node.right)))
+}
+
+impl<K: Ord, V> Treap<K, V> {
+    pub fn new() -> Self {
+        Self::with_seed(0x7265_6170)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.tree.head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.head.is_none()
+    }
+
+    pub fn height(&self) -> usize {
+        height(&self.tree.head)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut curr = self.tree.head.as_deref_mut();
+        while let Some(node) = curr {
+            curr = match key.cmp(&node.value.key) {
+                Ordering::Less => node.left.as_deref_mut(),
+                Ordering::Greater => node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value.value),
+            };
+        }
+        None
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        let (left, right) = split(self.tree.head.take(), &key);
+        let entry = TreapEntry {
+            key,
+            value,
+            priority: self.rng.next_u64(),
+            size: 1,
+        };
+        let node = Some(BinaryNode::boxed(entry, None, None));
+        self.tree.head = merge(merge(left, node), right);
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        remove(&mut self.tree.head, key)
+    }
+
+    // like `BTreeMap::split_off`: keeps the keys below `key` and returns the
+    // rest as a treap of its own
+    pub fn split_off(&mut self, key: &K) -> Self {
+        let (left, right) = split(self.tree.head.take(), key);
+        self.tree.head = left;
+
+        let mut out = Self::with_seed(self.rng.next_u64());
+        out.tree.head = right;
+        out
+    }
+
+    // the inverse of `split_off`, every key in other has to be larger than
+    // every key here
+    pub fn merge(&mut self, mut other: Self) {
+        if let (Some(last), Some(first)) = (
+            spine_end(&self.tree.head, true),
+            spine_end(&other.tree.head, false),
+        ) {
+            assert!(last < first, "merged treaps overlap");
+        }
+
+        self.tree.head = merge(self.tree.head.take(), other.tree.head.take());
+    }
+
+    // the entry with `index` smaller keys
+    pub fn select(&self, mut index: usize) -> Option<(&K, &V)> {
+        let mut curr = self.tree.head.as_deref();
+        while let Some(node) = curr {
+            let left = size(&node.left);
+            curr = match index.cmp(&left) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Equal => return Some((&node.value.key, &node.value.value)),
+                Ordering::Greater => {
+                    index -= left + 1;
+                    node.right.as_deref()
+                }
+            };
+        }
+        None
+    }
+
+    // keys in order, priorities never above the parent's and every cached
+    // subtree size right
+    pub fn is_valid(&self) -> bool {
+        check(&self.tree.head, None, None, u64::MAX).is_some()
+    }
+}
+
+keyed_lookups!(Treap);
+keyed_tree!(Treap);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_sorted_inserts() {
+        let len = 100_000;
+        let tree: Treap<usize, usize> = (0..len).map(|key| (key, key)).collect();
+        assert!(tree.is_valid());
+        assert_eq!(tree.len(), len);
+        // a random tree is about 4.3 ln n deep at the most
+        assert!(tree.height() as f64 <= 4.5 * (len as f64).ln());
+        assert_eq!(tree.select(777), Some((&777, &777)));
+    }
+
+    #[test]
+    fn test_split_merge() {
+        let mut left: Treap<i32, ()> = (0..100).map(|key| (key, ())).collect();
+        let right = left.split_off(&40);
+        assert!(left.is_valid() && right.is_valid());
+        assert_eq!(left.len(), 40);
+        assert!(right.iter().map(|(key, _)| *key).eq(40..100));
+
+        let mut empty = Treap::new();
+        let mut all = left.split_off(&0);
+        assert!(left.is_empty());
+        all.merge(right);
+        all.merge(Treap::new());
+        empty.merge(all);
+        assert!(empty.is_valid());
+        assert!(empty.iter().map(|(key, _)| *key).eq(0..100));
+    }
+
+    #[test]
+    #[should_panic(expected = "overlap")]
+    fn test_merge_overlap() {
+        let mut left: Treap<i32, ()> = (0..10).map(|key| (key, ())).collect();
+        let right = left.clone();
+        left.merge(right);
+    }
+
+    #[test]
+    fn test_against_btree_map() {
+        let rng = &mut Rng::new(47);
+        let mut tree = Treap::with_seed(1);
+        let mut map = BTreeMap::new();
+
+        for _ in 0..4000 {
+            let key = rng.below(300);
+            if rng.chance(0.55) {
+                assert_eq!(tree.insert(key, key + 1), map.insert(key, key + 1));
+            } else {
+                assert_eq!(tree.remove(&key), map.remove(&key));
+            }
+
+            assert!(tree.is_valid());
+            assert_eq!(tree.len(), map.len());
+        }
+
+        assert!(tree.iter().eq(map.iter()));
+        assert!(tree.range(..=150).eq(map.range(..=150)));
+    }
+}
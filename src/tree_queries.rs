@@ -0,0 +1,429 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    binary_tree::{compare, BinaryNode, BinaryTree, Link},
+    queue::Queue,
+};
+
+// nodes in post order, built as root-right-left pre order and reversed
+fn post_order_nodes<T>(link: &Link<T>) -> Vec<&BinaryNode<T>> {
+    let mut out = vec![];
+    let mut stack: Vec<&BinaryNode<T>> = link.as_deref().into_iter().collect();
+    while let Some(node) = stack.pop() {
+        out.push(node);
+        stack.extend(node.left.as_deref());
+        stack.extend(node.right.as_deref());
+    }
+    out.reverse();
+    out
+}
+
+// combines results bottom up without recursing: `f` sees each node with
+// what it returned for the left and right subtree, None where there is none
+pub(crate) fn fold<'a, T, R>(
+    link: &'a Link<T>,
+    mut f: impl FnMut(&'a BinaryNode<T>, Option<R>, Option<R>) -> R,
+) -> Option<R> {
+    let mut results = vec![];
+    for node in post_order_nodes(link) {
+        // the right subtree finished last, so its result is on top
+        let right = node.right.as_ref().and_then(|_| results.pop());
+        let left = node.left.as_ref().and_then(|_| results.pop());
+        results.push(f(node, left, right));
+    }
+    results.pop()
+}
+
+impl<T> BinaryTree<T> {
+    // nodes on the longest path down, 0 for an empty tree
+    pub fn height(&self) -> usize {
+        fold(&self.head, |_, left, right| {
+            1 + left.unwrap_or(0).max(right.unwrap_or(0))
+        })
+        .unwrap_or(0)
+    }
+
+    pub fn size(&self) -> usize {
+        self.pre_order().count()
+    }
+
+    // edges on the longest path between any two nodes
+    pub fn diameter(&self) -> usize {
+        // (height, diameter) of every subtree
+        fold(&self.head, |_, left, right| {
+            let (left_height, left_diameter) = left.unwrap_or((0, 0));
+            let (right_height, right_diameter) = right.unwrap_or((0, 0));
+            let through = left_height + right_height;
+            (
+                1 + left_height.max(right_height),
+                through.max(left_diameter).max(right_diameter),
+            )
+        })
+        .map_or(0, |(_, diameter)| diameter)
+    }
+
+    // no node whose subtrees differ in height by more than one
+    pub fn is_balanced(&self) -> bool {
+        fold(&self.head, |_, left, right| {
+            let left: usize = left.unwrap_or(Some(0))?;
+            let right = right.unwrap_or(Some(0))?;
+            (left.abs_diff(right) <= 1).then_some(1 + left.max(right))
+        })
+        .is_none_or(|height| height.is_some())
+    }
+
+    // every level full except the last, which is filled from the left; in
+    // level order no node may come after the first gap
+    pub fn is_complete(&self) -> bool {
+        let mut queue = Queue::new();
+        queue.enqueue(self.head.as_deref());
+        let mut gap = false;
+
+        while let Some(link) = queue.deque() {
+            match link {
+                Some(_) if gap => return false,
+                Some(node) => {
+                    queue.enqueue(node.left.as_deref());
+                    queue.enqueue(node.right.as_deref());
+                }
+                None => gap = true,
+            }
+        }
+        true
+    }
+
+    // every node has no children or two
+    pub fn is_full(&self) -> bool {
+        post_order_nodes(&self.head)
+            .iter()
+            .all(|node| node.left.is_some() == node.right.is_some())
+    }
+
+    pub fn mirror(&mut self) {
+        let mut stack: Vec<&mut BinaryNode<T>> = self.head.as_deref_mut().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            std::mem::swap(&mut node.left, &mut node.right);
+            stack.extend(node.left.as_deref_mut());
+            stack.extend(node.right.as_deref_mut());
+        }
+    }
+}
+
+impl<T: PartialEq> BinaryTree<T> {
+    // values from the root down to the first node in pre order holding value
+    pub fn path_to(&self, value: &T) -> Option<Vec<&T>> {
+        let mut path = vec![];
+        let mut stack: Vec<(&BinaryNode<T>, usize)> = self
+            .head
+            .as_deref()
+            .map(|head| (head, 0))
+            .into_iter()
+            .collect();
+
+        while let Some((node, depth)) = stack.pop() {
+            path.truncate(depth);
+            path.push(&node.value);
+            if node.value == *value {
+                return Some(path);
+            }
+
+            stack.extend(node.right.as_deref().map(|right| (right, depth + 1)));
+            stack.extend(node.left.as_deref().map(|left| (left, depth + 1)));
+        }
+        None
+    }
+
+    // the deepest node on both root paths, found by walking them side by
+    // side; O(n) per query, see `EulerTourLca` for many queries
+    pub fn lowest_common_ancestor(&self, a: &T, b: &T) -> Option<&T> {
+        let a = self.path_to(a)?;
+        let b = self.path_to(b)?;
+        a.iter()
+            .zip(b.iter())
+            .take_while(|(a, b)| std::ptr::eq(**a, **b))
+            .last()
+            .map(|(ancestor, _)| *ancestor)
+    }
+
+    // the first subtree in level order with value at its root
+    pub fn find_subtree(&self, value: &T) -> Option<&Link<T>> {
+        let mut queue = Queue::new();
+        queue.enqueue(&self.head);
+
+        while let Some(link) = queue.deque() {
+            let node = link.as_deref()?;
+            if node.value == *value {
+                return Some(link);
+            }
+            for child in [&node.left, &node.right] {
+                if child.is_some() {
+                    queue.enqueue(child);
+                }
+            }
+        }
+        None
+    }
+
+    // whether some node of this tree roots a copy of other; an empty tree
+    // is part of every tree
+    pub fn contains_subtree(&self, other: &BinaryTree<T>) -> bool {
+        let Some(root) = other.head.as_deref() else {
+            return true;
+        };
+
+        let mut stack = vec![&self.head];
+        while let Some(link) = stack.pop() {
+            let Some(node) = link.as_deref() else {
+                continue;
+            };
+            if node.value == root.value && compare(link, &other.head) {
+                return true;
+            }
+            stack.push(&node.left);
+            stack.push(&node.right);
+        }
+        false
+    }
+}
+
+// the subtree of one tree at a value equals the one of another at a value
+pub fn subtrees_equal<T: PartialEq>(a: &BinaryTree<T>, at: &T, b: &BinaryTree<T>, bt: &T) -> bool {
+    match (a.find_subtree(at), b.find_subtree(bt)) {
+        (Some(a), Some(b)) => compare(a, b),
+        _ => false,
+    }
+}
+
+// lowest common ancestors in O(1) after O(n log n) preprocessing: the LCA
+// of two nodes is the shallowest node an Euler tour passes between them,
+// which a sparse table of range minima answers with two overlapping lookups.
+// values should be distinct, with repeats the first in pre order counts
+#[derive(Clone, Debug)]
+pub struct EulerTourLca<'a, T> {
+    // every node each time the tour passes it, with its depth
+    tour: Vec<&'a T>,
+    depths: Vec<usize>,
+    first: HashMap<&'a T, usize>,
+    // sparse[k][i] is the shallowest position in tour[i..i + 2^k]
+    sparse: Vec<Vec<usize>>,
+}
+
+impl<'a, T: Eq + Hash> EulerTourLca<'a, T> {
+    pub fn new(tree: &'a BinaryTree<T>) -> Self {
+        let mut tour = vec![];
+        let mut depths = vec![];
+        // (node, depth, next child to visit)
+        let mut stack = vec![];
+        if let Some(head) = tree.head.as_deref() {
+            tour.push(&head.value);
+            depths.push(0);
+            stack.push((head, 0, 0));
+        }
+
+        while let Some(top) = stack.last_mut() {
+            let (node, depth, child) = *top;
+            top.2 += 1;
+
+            let next = match child {
+                0 => node.left.as_deref(),
+                1 => node.right.as_deref(),
+                _ => {
+                    // back up in the parent
+                    stack.pop();
+                    if let Some(&(parent, depth, _)) = stack.last() {
+                        tour.push(&parent.value);
+                        depths.push(depth);
+                    }
+                    continue;
+                }
+            };
+            if let Some(next) = next {
+                tour.push(&next.value);
+                depths.push(depth + 1);
+                stack.push((next, depth + 1, 0));
+            }
+        }
+
+        let mut first = HashMap::new();
+        for (i, &value) in tour.iter().enumerate() {
+            first.entry(value).or_insert(i);
+        }
+
+        let mut sparse = vec![(0..tour.len()).collect::<Vec<_>>()];
+        let mut width = 1;
+        while 2 * width <= tour.len() {
+            let prev = sparse.last().unwrap();
+            let row = (0..=tour.len() - 2 * width)
+                .map(|i| {
+                    let (a, b) = (prev[i], prev[i + width]);
+                    if depths[a] <= depths[b] {
+                        a
+                    } else {
+                        b
+                    }
+                })
+                .collect();
+            sparse.push(row);
+            width *= 2;
+        }
+
+        Self {
+            tour,
+            depths,
+            first,
+            sparse,
+        }
+    }
+
+    pub fn query(&self, a: &T, b: &T) -> Option<&'a T> {
+        let a = *self.first.get(a)?;
+        let b = *self.first.get(b)?;
+        let (low, high) = (a.min(b), a.max(b));
+
+        let level = (high - low + 1).ilog2() as usize;
+        let left = self.sparse[level][low];
+        let right = self.sparse[level][high + 1 - (1 << level)];
+        let shallowest = match self.depths[left] <= self.depths[right] {
+            true => left,
+            false => right,
+        };
+        Some(self.tour[shallowest])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        generators::Rng,
+        tests::tree::{sorted_tree, tree, tree2},
+        tree_serialization::{from_leetcode, from_level_order},
+    };
+
+    #[test]
+    fn test_metrics() {
+        let (tree, tree2, sorted) = (tree(), tree2(), sorted_tree());
+        let empty = BinaryTree::<isize>::new();
+
+        assert_eq!([tree.height(), tree2.height(), sorted.height()], [4, 5, 3]);
+        assert_eq!([tree.size(), tree2.size(), sorted.size()], [10, 11, 7]);
+        assert_eq!(
+            [tree.diameter(), tree2.diameter(), sorted.diameter()],
+            [6, 7, 4]
+        );
+        assert_eq!((empty.height(), empty.size(), empty.diameter()), (0, 0, 0));
+
+        assert!(tree.is_balanced() && !tree2.is_balanced() && sorted.is_balanced());
+        assert!(!tree.is_complete() && !tree2.is_complete() && sorted.is_complete());
+        assert!(!tree.is_full() && !tree2.is_full() && sorted.is_full());
+        assert!(empty.is_balanced() && empty.is_complete() && empty.is_full());
+
+        let complete = from_leetcode::<i32>("[1,2,3,4,5,6]").unwrap();
+        assert!(complete.is_complete() && !complete.is_full());
+        let gap = from_leetcode::<i32>("[1,2,3,4,null,6]").unwrap();
+        assert!(!gap.is_complete() && gap.is_balanced());
+    }
+
+    #[test]
+    fn test_paths() {
+        let tree = tree();
+        assert_eq!(tree.path_to(&29), Some(vec![&20, &50, &30, &29]));
+        assert_eq!(tree.path_to(&20), Some(vec![&20]));
+        assert_eq!(tree.path_to(&99), None);
+
+        let lca = EulerTourLca::new(&tree);
+        for (a, b, ancestor) in [
+            (45, 29, Some(30)),
+            (45, 100, Some(50)),
+            (45, 7, Some(20)),
+            (7, 5, Some(5)),
+            (15, 15, Some(15)),
+            (15, 99, None),
+        ] {
+            assert_eq!(tree.lowest_common_ancestor(&a, &b).copied(), ancestor);
+            assert_eq!(lca.query(&a, &b).copied(), ancestor);
+        }
+    }
+
+    #[test]
+    fn test_lca_random() {
+        let rng = &mut Rng::new(49);
+
+        for _ in 0..20 {
+            // distinct values in level order, stopping once no slots are open
+            let mut values = vec![];
+            let mut open = 1;
+            while open > 0 && values.len() < 300 {
+                open -= 1;
+                if values.is_empty() || rng.chance(0.7) {
+                    values.push(Some(values.len()));
+                    open += 2;
+                } else {
+                    values.push(None);
+                }
+            }
+            let tree = from_level_order(values.clone()).unwrap();
+            let lca = EulerTourLca::new(&tree);
+
+            for _ in 0..200 {
+                let a = rng.below(values.len());
+                let b = rng.below(values.len());
+                assert_eq!(lca.query(&a, &b), tree.lowest_common_ancestor(&a, &b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut tree = sorted_tree();
+        tree.mirror();
+        assert!(tree.in_order().copied().eq([100, 50, 30, 20, 15, 10, 5]));
+        tree.mirror();
+        assert!(tree == sorted_tree());
+    }
+
+    #[test]
+    fn test_subtrees() {
+        let (tree, tree2) = (tree(), tree2());
+        let part = from_leetcode::<isize>("[30,45,29]").unwrap();
+
+        assert!(tree.contains_subtree(&part));
+        assert!(!tree2.contains_subtree(&part));
+        assert!(tree.contains_subtree(&BinaryTree::new()));
+        assert!(tree.contains_subtree(&tree));
+        assert!(!part.contains_subtree(&tree));
+
+        assert!(subtrees_equal(&tree, &10, &tree2, &10));
+        assert!(!subtrees_equal(&tree, &50, &tree2, &50));
+        assert!(subtrees_equal(&tree, &30, &part, &30));
+        assert!(!subtrees_equal(&tree, &99, &tree2, &99));
+        assert_eq!(tree.find_subtree(&5).map(|link| link.is_some()), Some(true));
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let len = 300_000;
+        let mut node = BinaryNode::new(0, None, None);
+        for value in 1..len {
+            node = BinaryNode::new(value, Some(Box::new(node)), None);
+        }
+        let mut tree = BinaryTree::new();
+        tree.set_head(node);
+
+        assert_eq!((tree.height(), tree.size()), (len, len));
+        assert_eq!(tree.diameter(), len - 1);
+        assert!(!tree.is_balanced() && !tree.is_complete() && !tree.is_full());
+        assert_eq!(tree.path_to(&0).map(|path| path.len()), Some(len));
+        assert_eq!(EulerTourLca::new(&tree).query(&0, &7), Some(&7));
+
+        let copy = tree.clone();
+        assert!(tree.contains_subtree(&tree));
+        assert!(subtrees_equal(&tree, &(len - 1), &copy, &(len - 1)));
+        assert!(subtrees_equal(&tree, &(len / 2), &copy, &(len / 2)));
+        assert!(!subtrees_equal(&tree, &(len - 1), &copy, &(len - 2)));
+
+        tree.mirror();
+        assert!(!tree.contains_subtree(&copy));
+        assert_eq!(tree.in_order().next(), Some(&(len - 1)));
+    }
+}
@@ -0,0 +1,474 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    binary_tree::{BinaryNode, BinaryTree, Link},
+    stack::Stack,
+    tree_queries::fold,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    // the root on top and each level below joined by box-drawing lines
+    #[default]
+    TopDown,
+    // the top-down drawing turned a quarter counterclockwise: the root on
+    // the left, its right subtree above it and its left subtree below
+    Sideways,
+}
+
+// a drawing of a tree, configured with the methods below and written out
+// through `Display`
+pub struct Render<'a, T> {
+    tree: &'a BinaryTree<T>,
+    layout: Layout,
+    format: Box<dyn Fn(&T) -> String + 'a>,
+    max_label: usize,
+    max_width: usize,
+}
+
+impl<T> BinaryTree<T> {
+    pub fn render(&self) -> Render<'_, T>
+    where
+        T: Display,
+    {
+        self.render_with(|value| value.to_string())
+    }
+
+    pub fn render_with<'a>(&'a self, format: impl Fn(&T) -> String + 'a) -> Render<'a, T> {
+        Render {
+            tree: self,
+            layout: Layout::default(),
+            format: Box::new(format),
+            max_label: usize::MAX,
+            max_width: usize::MAX,
+        }
+    }
+}
+
+impl<T: Display> Display for BinaryTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render().fmt(f)
+    }
+}
+
+// cuts text to max chars, the last one an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    match text.chars().count() <= max {
+        true => text.to_string(),
+        false => text
+            .chars()
+            .take(max.saturating_sub(1))
+            .chain(['…'])
+            .collect(),
+    }
+}
+
+// a subtree drawn top down, `root` the column its root label is centred on
+struct Block {
+    lines: Vec<String>,
+    width: usize,
+    root: usize,
+}
+
+const GAP: usize = 2;
+
+fn row_of(block: &Option<Block>, row: usize) -> &str {
+    block
+        .as_ref()
+        .and_then(|block| block.lines.get(row))
+        .map_or("", |line| line.as_str())
+}
+
+// puts the label over its subtrees, shifting them right where a label or
+// connector would otherwise start left of column 0
+fn join(label: String, left: Option<Block>, right: Option<Block>) -> Block {
+    let width = label.chars().count();
+    let left_width = left.as_ref().map_or(0, |left| left.width + GAP);
+    let left_root = left.as_ref().map(|left| left.root as isize);
+    let right_root = right
+        .as_ref()
+        .map(|right| (left_width + right.root) as isize);
+
+    let center = match (left_root, right_root) {
+        (Some(left), Some(right)) => (left + right) / 2,
+        (Some(left), None) => left + 2,
+        (None, Some(right)) => right - 2,
+        (None, None) => (width / 2) as isize,
+    };
+    let start = center - (width / 2) as isize;
+    let shift = (-start).max(0);
+    let at = |column: isize| (column + shift) as usize;
+
+    let children = match &right {
+        Some(right) => left_width + right.width,
+        None => left_width.saturating_sub(GAP),
+    };
+    let total = (children + at(0)).max(at(start) + width);
+    let mut lines = vec![format!("{}{label}", " ".repeat(at(start)))];
+
+    if left.is_some() || right.is_some() {
+        let mut connector = vec![' '; total];
+        for cell in
+            &mut connector[at(left_root.unwrap_or(center))..=at(right_root.unwrap_or(center))]
+        {
+            *cell = '─';
+        }
+        connector[at(center)] = match (left_root, right_root) {
+            (Some(_), Some(_)) => '┴',
+            (Some(_), None) => '┘',
+            _ => '└',
+        };
+        if let Some(left) = left_root {
+            connector[at(left)] = '┌';
+        }
+        if let Some(right) = right_root {
+            connector[at(right)] = '┐';
+        }
+        lines.push(
+            connector
+                .into_iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string(),
+        );
+
+        let rows = |block: &Option<Block>| block.as_ref().map_or(0, |block| block.lines.len());
+        for row in 0..rows(&left).max(rows(&right)) {
+            let line = format!(
+                "{}{:<left_width$}{}",
+                " ".repeat(at(0)),
+                row_of(&left, row),
+                row_of(&right, row)
+            );
+            lines.push(line.trim_end().to_string());
+        }
+    }
+
+    Block {
+        lines,
+        width: total,
+        root: at(center),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Root,
+    Right,
+    Left,
+}
+
+impl<T> Render<'_, T> {
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    // labels longer than this are cut short with an ellipsis
+    pub fn max_label(mut self, max: usize) -> Self {
+        self.max_label = max;
+        self
+    }
+
+    // and so are lines, for trees too wide for the terminal
+    pub fn max_width(mut self, max: usize) -> Self {
+        self.max_width = max;
+        self
+    }
+
+    fn label(&self, value: &T) -> String {
+        truncate(&(self.format)(value), self.max_label)
+    }
+
+    // the drawing before lines are cut to max_width
+    pub fn lines(&self) -> Vec<String> {
+        match self.layout {
+            Layout::TopDown => fold(&self.tree.head, |node, left, right| {
+                join(self.label(&node.value), left, right)
+            })
+            .map_or(vec![], |block| block.lines),
+            Layout::Sideways => self.sideways(),
+        }
+    }
+
+    // a reverse in order walk, so the right subtree comes out first
+    fn sideways(&self) -> Vec<String> {
+        enum Step<'b, T> {
+            Visit(&'b BinaryNode<T>, String, Side),
+            Line(String),
+        }
+
+        let mut lines = vec![];
+        let mut stack: Vec<Step<T>> = self
+            .tree
+            .head
+            .as_deref()
+            .map(|head| Step::Visit(head, String::new(), Side::Root))
+            .into_iter()
+            .collect();
+
+        while let Some(step) = stack.pop() {
+            let (node, prefix, side) = match step {
+                Step::Visit(node, prefix, side) => (node, prefix, side),
+                Step::Line(line) => {
+                    lines.push(line);
+                    continue;
+                }
+            };
+
+            // a child on the far side of its parent from the root continues
+            // the parent's vertical line
+            let (connector, above, below) = match side {
+                Side::Root => ("", "", ""),
+                Side::Right => ("┌── ", "    ", "│   "),
+                Side::Left => ("└── ", "│   ", "    "),
+            };
+            if let Some(left) = node.left.as_deref() {
+                stack.push(Step::Visit(left, format!("{prefix}{below}"), Side::Left));
+            }
+            let label = self.label(&node.value);
+            stack.push(Step::Line(format!("{prefix}{connector}{label}")));
+            if let Some(right) = node.right.as_deref() {
+                stack.push(Step::Visit(right, format!("{prefix}{above}"), Side::Right));
+            }
+        }
+        lines
+    }
+
+    // Graphviz source; a missing child next to a present one becomes an
+    // invisible node so dot still draws the other child to its side
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph {\n    node [shape=circle];\n");
+        let mut stack: Vec<(&BinaryNode<T>, usize)> = self
+            .tree
+            .head
+            .as_deref()
+            .map(|head| (head, 0))
+            .into_iter()
+            .collect();
+        let mut ids = 1;
+
+        while let Some((node, id)) = stack.pop() {
+            let label = self
+                .label(&node.value)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            out.push_str(&format!("    n{id} [label=\"{label}\"];\n"));
+
+            if node.left.is_none() && node.right.is_none() {
+                continue;
+            }
+            for child in [&node.left, &node.right] {
+                let child_id = ids;
+                ids += 1;
+                match child.as_deref() {
+                    Some(child) => {
+                        out.push_str(&format!("    n{id} -> n{child_id};\n"));
+                        stack.push((child, child_id));
+                    }
+                    None => out.push_str(&format!(
+                        "    n{child_id} [style=invis];\n    n{id} -> n{child_id} [style=invis];\n"
+                    )),
+                }
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl<T> Display for Render<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.lines().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", truncate(line, self.max_width))?;
+        }
+        Ok(())
+    }
+}
+
+// labels for the nodes of one tree, in brackets where the other tree has no
+// node at the same place or one with another value. built bottom up over an
+// explicit stack, so long spines do not overflow
+fn marked<T: PartialEq + Display>(link: &Link<T>, other: &Link<T>) -> Link<String> {
+    let mut stack = Stack::new();
+    let mut done = Stack::new();
+    if let Some(node) = link.as_deref() {
+        stack.push((node, other.as_deref(), false));
+    }
+
+    while let Some((node, other, expanded)) = stack.pop() {
+        let (value, left, right) = match other {
+            Some(other) => (Some(&other.value), &other.left, &other.right),
+            None => (None, &None, &None),
+        };
+
+        if !expanded {
+            stack.push((node, other, true));
+            if let Some(child) = node.right.as_deref() {
+                stack.push((child, right.as_deref(), false));
+            }
+            if let Some(child) = node.left.as_deref() {
+                stack.push((child, left.as_deref(), false));
+            }
+            continue;
+        }
+
+        // the left subtree finished first, so its label sits below the right
+        let right = node.right.as_ref().and_then(|_| done.pop());
+        let left = node.left.as_ref().and_then(|_| done.pop());
+        let label = match value == Some(&node.value) {
+            true => node.value.to_string(),
+            false => format!("[{}]", node.value),
+        };
+        done.push(BinaryNode::boxed(label, left, right));
+    }
+
+    done.pop()
+}
+
+// both trees drawn top down next to each other with the nodes they do not
+// share in brackets, for assertion messages
+pub fn diff<T: PartialEq + Display>(left: &BinaryTree<T>, right: &BinaryTree<T>) -> String {
+    let mut marks = (BinaryTree::new(), BinaryTree::new());
+    marks.0.head = marked(&left.head, &right.head);
+    marks.1.head = marked(&right.head, &left.head);
+    let left = marks.0.render().lines();
+    let right = marks.1.render().lines();
+    let width = left
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    (0..left.len().max(right.len()))
+        .map(|row| {
+            let a = left.get(row).map_or("", |line| line.as_str());
+            let b = right.get(row).map_or("", |line| line.as_str());
+            format!("{a:<width$} │ {b}").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tests::tree::{tree, tree2},
+        tree_serialization::from_leetcode,
+    };
+
+    #[test]
+    fn test_top_down() {
+        let expected = [
+            "         20",
+            "    ┌─────┴─────┐",
+            "   50          10",
+            " ┌──┴───┐     ┌─┴──┐",
+            "100    30    15    5",
+            "      ┌─┴─┐      ┌─┘",
+            "     45  29      7",
+        ];
+        assert_eq!(tree().to_string(), expected.join("\n"));
+
+        let chain = from_leetcode::<i32>("[1,null,2,null,3]").unwrap();
+        assert_eq!(chain.to_string(), "1\n└─┐\n  2\n  └─┐\n    3");
+        assert_eq!(BinaryTree::<i32>::new().to_string(), "");
+    }
+
+    #[test]
+    fn test_sideways() {
+        let expected = [
+            "    ┌── 5",
+            "    │   └── 7",
+            "┌── 10",
+            "│   └── 15",
+            "20",
+            "│       ┌── 29",
+            "│   ┌── 30",
+            "│   │   └── 45",
+            "└── 50",
+            "    └── 100",
+        ];
+        let tree = tree();
+        assert_eq!(tree.render().layout(Layout::Sideways).lines(), expected);
+    }
+
+    #[test]
+    fn test_formatting() {
+        let tree = tree();
+        let hex = tree.render_with(|value| format!("{value:x}")).to_string();
+        assert!(hex.starts_with("        14\n") && hex.contains("1e") && !hex.contains("30"));
+
+        let short = tree.render().max_label(2).max_width(8).to_string();
+        let lines: Vec<_> = short.lines().collect();
+        assert!(lines.iter().all(|line| line.chars().count() <= 8));
+        assert_eq!(lines[4], "1…    3…");
+        assert!(lines[0].ends_with('…') && !lines.concat().contains("100"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let tree = from_leetcode::<String>("[a,\"b\",c,null,d]").unwrap();
+        let expected = [
+            "digraph {",
+            "    node [shape=circle];",
+            "    n0 [label=\"a\"];",
+            "    n0 -> n1;",
+            "    n0 -> n2;",
+            "    n2 [label=\"c\"];",
+            "    n1 [label=\"\\\"b\\\"\"];",
+            "    n3 [style=invis];",
+            "    n1 -> n3 [style=invis];",
+            "    n1 -> n4;",
+            "    n4 [label=\"d\"];",
+            "}",
+            "",
+        ];
+        assert_eq!(tree.render().to_dot(), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected = [
+            "             20            │              20",
+            "       ┌──────┴───────┐    │          ┌────┴────┐",
+            "      50             10    │         50        10",
+            "  ┌────┴────┐       ┌─┴──┐ │        ┌─┘       ┌─┴──┐",
+            "[100]     [30]     15    5 │      [30]       15    5",
+            "         ┌──┴──┐       ┌─┘ │     ┌──┴──┐         ┌─┘",
+            "       [45]  [29]      7   │   [45]  [29]        7",
+            "                           │   ┌─┘     └─┐",
+            "                           │ [49]      [21]",
+        ];
+        assert_eq!(diff(&tree(), &tree2()), expected.join("\n"));
+        assert!(!diff(&tree(), &tree()).contains('['));
+    }
+
+    #[test]
+    fn test_marked_deep_tree() {
+        // a left spine this long would overflow a recursive walk
+        let len = 1_000_000;
+        let spine = |last: usize| {
+            let mut node = BinaryNode::new(0, None, None);
+            for value in 1..len {
+                let value = if value == len - 1 { last } else { value };
+                node = BinaryNode::new(value, Some(Box::new(node)), None);
+            }
+            let mut tree = BinaryTree::new();
+            tree.set_head(node);
+            tree
+        };
+
+        let mut marks = BinaryTree::new();
+        marks.head = marked(&spine(len - 1).head, &spine(0).head);
+        let labels: Vec<_> = marks.pre_order().take(2).cloned().collect();
+        assert_eq!(labels, [format!("[{}]", len - 1), format!("{}", len - 2)]);
+        assert_eq!(marks.in_order().next(), Some(&"0".to_string()));
+        assert_eq!(marks.pre_order().count(), len);
+    }
+}
@@ -0,0 +1,441 @@
+use std::{collections::HashSet, fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{
+    binary_tree::{BinaryNode, BinaryTree, Link},
+    queue::Queue,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    // index of a value or hole that has no free child slot left to go into
+    Orphan(usize),
+    // byte offset of the first thing that did not parse
+    Syntax(usize),
+    // the traversals do not describe one and the same tree
+    Mismatch,
+    // rebuilding from traversals needs every value to be distinct
+    Duplicate,
+}
+
+// nodes in creation order with the indices of their children; children are
+// always created after their parent, so the boxes can be put together from
+// the back without recursing
+type Arena<T> = Vec<(T, Option<usize>, Option<usize>)>;
+
+fn assemble<T>(arena: Arena<T>) -> BinaryTree<T> {
+    let mut built: Vec<Link<T>> = (0..arena.len()).map(|_| None).collect();
+    for (i, (value, left, right)) in arena.into_iter().enumerate().rev() {
+        let left = left.and_then(|left| built[left].take());
+        let right = right.and_then(|right| built[right].take());
+        built[i] = Some(BinaryNode::boxed(value, left, right));
+    }
+
+    let mut tree = BinaryTree::new();
+    tree.head = built.first_mut().and_then(Option::take);
+    tree
+}
+
+// breadth first with a hole for every missing child of a present node,
+// trailing holes cut off
+pub fn to_level_order<T: Clone>(tree: &BinaryTree<T>) -> Vec<Option<T>> {
+    let mut out = vec![];
+    let mut queue = Queue::new();
+    queue.enqueue(tree.head.as_deref());
+
+    while let Some(link) = queue.deque() {
+        match link {
+            Some(node) => {
+                out.push(Some(node.value.clone()));
+                queue.enqueue(node.left.as_deref());
+                queue.enqueue(node.right.as_deref());
+            }
+            None => out.push(None),
+        }
+    }
+
+    while out.last().is_some_and(Option::is_none) {
+        out.pop();
+    }
+    out
+}
+
+// the inverse of `to_level_order`, but trailing holes may also be written out
+pub fn from_level_order<T>(values: Vec<Option<T>>) -> Result<BinaryTree<T>, Error> {
+    let mut arena: Arena<T> = vec![];
+    // (node, is the right slot) for every child slot still waiting for a value
+    let mut slots: Queue<(usize, bool)> = Queue::new();
+
+    for (i, value) in values.into_iter().enumerate() {
+        let slot = match i {
+            0 => None,
+            _ => Some(slots.deque().ok_or(Error::Orphan(i))?),
+        };
+        let Some(value) = value else {
+            continue;
+        };
+
+        let id = arena.len();
+        arena.push((value, None, None));
+        match slot {
+            Some((parent, false)) => arena[parent].1 = Some(id),
+            Some((parent, true)) => arena[parent].2 = Some(id),
+            None => {}
+        }
+        slots.enqueue((id, false));
+        slots.enqueue((id, true));
+    }
+
+    Ok(assemble(arena))
+}
+
+// LeetCode's notation, e.g. "[1,null,2,3]"
+pub fn to_leetcode<T: Clone + Display>(tree: &BinaryTree<T>) -> String {
+    let values: Vec<String> = to_level_order(tree)
+        .into_iter()
+        .map(|value| value.map_or("null".to_string(), |value| value.to_string()))
+        .collect();
+    format!("[{}]", values.join(","))
+}
+
+pub fn from_leetcode<T: FromStr>(text: &str) -> Result<BinaryTree<T>, Error> {
+    let start = text.len() - text.trim_start().len();
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or(Error::Syntax(start))?;
+    if inner.trim().is_empty() {
+        return Ok(BinaryTree::new());
+    }
+
+    let mut offset = start + 1;
+    let mut values = vec![];
+    for token in inner.split(',') {
+        let value = match token.trim() {
+            "null" => None,
+            value => Some(value.parse().map_err(|_| Error::Syntax(offset))?),
+        };
+        values.push(value);
+        offset += token.len() + 1;
+    }
+
+    from_level_order(values)
+}
+
+enum Step<'a, T> {
+    Node(&'a BinaryNode<T>),
+    Text(&'static str),
+}
+
+// every node followed by its subtrees in parentheses, "20(10(5)(15))(50)";
+// a missing right child is left out and a missing left one written as "()"
+pub fn to_parens<T: Display>(tree: &BinaryTree<T>) -> String {
+    let mut out = String::new();
+    let mut stack: Vec<Step<T>> = tree.head.as_deref().map(Step::Node).into_iter().collect();
+
+    while let Some(step) = stack.pop() {
+        let node = match step {
+            Step::Text(text) => {
+                out.push_str(text);
+                continue;
+            }
+            Step::Node(node) => node,
+        };
+        out.push_str(&node.value.to_string());
+
+        // pushed in reverse, the stack hands them back in writing order
+        if let Some(right) = node.right.as_deref() {
+            stack.extend([Step::Text(")"), Step::Node(right), Step::Text("(")]);
+        }
+        if node.left.is_some() || node.right.is_some() {
+            stack.push(Step::Text(")"));
+            stack.extend(node.left.as_deref().map(Step::Node));
+            stack.push(Step::Text("("));
+        }
+    }
+
+    out
+}
+
+pub fn from_parens<T: FromStr>(text: &str) -> Result<BinaryTree<T>, Error> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    let skip_space = |pos: &mut usize| {
+        while bytes.get(*pos).is_some_and(u8::is_ascii_whitespace) {
+            *pos += 1;
+        }
+    };
+    let parse_value = |pos: &mut usize| -> Result<T, Error> {
+        let start = *pos;
+        while bytes
+            .get(*pos)
+            .is_some_and(|&b| b != b'(' && b != b')' && !b.is_ascii_whitespace())
+        {
+            *pos += 1;
+        }
+        text[start..*pos].parse().map_err(|_| Error::Syntax(start))
+    };
+
+    skip_space(&mut pos);
+    if pos == bytes.len() {
+        return Ok(BinaryTree::new());
+    }
+
+    let mut arena: Arena<T> = vec![(parse_value(&mut pos)?, None, None)];
+    // (node, children opened so far) for the nodes whose parentheses are open
+    let mut stack = vec![(0, 0)];
+
+    loop {
+        skip_space(&mut pos);
+        match bytes.get(pos) {
+            Some(b'(') => {
+                let (parent, opened) = stack.last_mut().unwrap();
+                if *opened == 2 {
+                    return Err(Error::Syntax(pos));
+                }
+                *opened += 1;
+                let (parent, right) = (*parent, *opened == 2);
+
+                pos += 1;
+                skip_space(&mut pos);
+                if bytes.get(pos) == Some(&b')') {
+                    pos += 1;
+                    continue;
+                }
+
+                let id = arena.len();
+                arena.push((parse_value(&mut pos)?, None, None));
+                match right {
+                    false => arena[parent].1 = Some(id),
+                    true => arena[parent].2 = Some(id),
+                }
+                stack.push((id, 0));
+            }
+            // closes the child on top, never the root
+            Some(b')') if stack.len() > 1 => {
+                stack.pop();
+                pos += 1;
+            }
+            None if stack.len() == 1 => break,
+            _ => return Err(Error::Syntax(pos)),
+        }
+    }
+
+    Ok(assemble(arena))
+}
+
+// builds along `order`, which is pre order, or post order read backwards
+// with left and right swapped; the stack holds the path of nodes whose in
+// order position has not been reached yet
+fn from_traversals<'a, T, I, J>(order: I, in_order: J, mirrored: bool) -> Result<Arena<T>, Error>
+where
+    T: Clone + PartialEq + 'a,
+    I: Iterator<Item = &'a T>,
+    J: Iterator<Item = &'a T>,
+{
+    let mut in_order = in_order.peekable();
+    let mut arena: Arena<T> = vec![];
+    let mut stack: Vec<usize> = vec![];
+
+    for value in order {
+        let id = arena.len();
+        arena.push((value.clone(), None, None));
+
+        let mut parent = None;
+        while let Some(&top) = stack.last() {
+            if in_order.peek() != Some(&&arena[top].0) {
+                break;
+            }
+            parent = stack.pop();
+            in_order.next();
+        }
+
+        // a node that has been passed in order takes the new one on its far
+        // side, otherwise it becomes the near child of the top of the path
+        let slot = match (parent, stack.last()) {
+            (Some(parent), _) => &mut arena[parent],
+            (None, Some(&top)) => &mut arena[top],
+            (None, None) if id == 0 => {
+                stack.push(id);
+                continue;
+            }
+            (None, None) => return Err(Error::Mismatch),
+        };
+        let slot = match parent.is_some() != mirrored {
+            true => &mut slot.2,
+            false => &mut slot.1,
+        };
+        if slot.is_some() {
+            return Err(Error::Mismatch);
+        }
+        *slot = Some(id);
+        stack.push(id);
+    }
+
+    Ok(arena)
+}
+
+fn check_distinct<T: Eq + Hash>(order: &[T], in_order: &[T]) -> Result<(), Error> {
+    if order.len() != in_order.len() {
+        return Err(Error::Mismatch);
+    }
+    if order.iter().collect::<HashSet<_>>().len() != order.len() {
+        return Err(Error::Duplicate);
+    }
+    Ok(())
+}
+
+pub fn from_pre_in_order<T>(pre_order: &[T], in_order: &[T]) -> Result<BinaryTree<T>, Error>
+where
+    T: Clone + Eq + Hash,
+{
+    check_distinct(pre_order, in_order)?;
+    let tree = assemble(from_traversals(pre_order.iter(), in_order.iter(), false)?);
+
+    // inputs that are not traversals of one tree still build something
+    if !tree.pre_order().eq(pre_order) || !tree.in_order().eq(in_order) {
+        return Err(Error::Mismatch);
+    }
+    Ok(tree)
+}
+
+pub fn from_post_in_order<T>(post_order: &[T], in_order: &[T]) -> Result<BinaryTree<T>, Error>
+where
+    T: Clone + Eq + Hash,
+{
+    check_distinct(post_order, in_order)?;
+    let tree = assemble(from_traversals(
+        post_order.iter().rev(),
+        in_order.iter().rev(),
+        true,
+    )?);
+
+    if !tree.post_order().eq(post_order) || !tree.in_order().eq(in_order) {
+        return Err(Error::Mismatch);
+    }
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::tree::{sorted_tree, tree, tree2};
+
+    #[test]
+    fn test_level_order() {
+        assert_eq!(
+            to_leetcode(&tree()),
+            "[20,50,10,100,30,15,5,null,null,45,29,null,null,7]"
+        );
+        assert_eq!(
+            to_leetcode(&tree2()),
+            "[20,50,10,30,null,15,5,45,29,null,null,7,null,49,null,null,21]"
+        );
+        assert_eq!(to_leetcode(&sorted_tree()), "[20,10,50,5,15,30,100]");
+        assert_eq!(to_leetcode(&BinaryTree::<isize>::new()), "[]");
+
+        for fixture in [tree(), tree2(), sorted_tree(), BinaryTree::new()] {
+            assert!(from_level_order(to_level_order(&fixture)).unwrap() == fixture);
+            assert!(from_leetcode::<isize>(&to_leetcode(&fixture)).unwrap() == fixture);
+        }
+
+        let tree = from_leetcode::<i32>(" [1, null, 2, 3, null, null, null] ").unwrap();
+        assert_eq!(to_leetcode(&tree), "[1,null,2,3]");
+        assert_eq!(from_leetcode::<i32>("[1,2]x"), Err(Error::Syntax(0)));
+        assert_eq!(from_leetcode::<i32>("[1,2,y]"), Err(Error::Syntax(5)));
+        assert_eq!(
+            from_leetcode::<i32>("[1,null,null,4]").err(),
+            Some(Error::Orphan(3))
+        );
+    }
+
+    #[test]
+    fn test_parens() {
+        assert_eq!(to_parens(&tree()), "20(50(100)(30(45)(29)))(10(15)(5(7)))");
+        assert_eq!(
+            to_parens(&tree2()),
+            "20(50(30(45(49))(29()(21))))(10(15)(5(7)))"
+        );
+        assert_eq!(to_parens(&BinaryTree::<isize>::new()), "");
+
+        for fixture in [tree(), tree2(), sorted_tree(), BinaryTree::new()] {
+            assert!(from_parens::<isize>(&to_parens(&fixture)).unwrap() == fixture);
+        }
+
+        let tree = from_parens::<i32>(" -1 ( ) ( 2 (3) () ) ").unwrap();
+        assert_eq!(to_parens(&tree), "-1()(2(3))");
+        assert_eq!(
+            from_parens::<i32>("1(2)(3)(4)").err(),
+            Some(Error::Syntax(7))
+        );
+        assert_eq!(from_parens::<i32>("1(2").err(), Some(Error::Syntax(3)));
+        assert_eq!(from_parens::<i32>("1)").err(), Some(Error::Syntax(1)));
+        assert_eq!(from_parens::<i32>("1(a)").err(), Some(Error::Syntax(2)));
+    }
+
+    #[test]
+    fn test_from_traversals() {
+        for fixture in [tree(), tree2(), sorted_tree(), BinaryTree::new()] {
+            let pre: Vec<isize> = fixture.pre_order().copied().collect();
+            let post: Vec<isize> = fixture.post_order().copied().collect();
+            let ino: Vec<isize> = fixture.in_order().copied().collect();
+
+            assert!(from_pre_in_order(&pre, &ino).unwrap() == fixture);
+            assert!(from_post_in_order(&post, &ino).unwrap() == fixture);
+        }
+
+        assert_eq!(
+            from_pre_in_order(&[1, 2], &[1]).err(),
+            Some(Error::Mismatch)
+        );
+        assert_eq!(
+            from_pre_in_order(&[1, 1], &[1, 1]).err(),
+            Some(Error::Duplicate)
+        );
+        assert_eq!(
+            from_pre_in_order(&[1, 2, 3], &[3, 1, 2]).err(),
+            Some(Error::Mismatch)
+        );
+        assert_eq!(
+            from_post_in_order(&[1, 2, 3], &[2, 3, 1]).err(),
+            Some(Error::Mismatch)
+        );
+        assert_eq!(
+            from_pre_in_order(&[1, 2], &[3, 4]).err(),
+            Some(Error::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        // a long zig-zag path, nothing on the way may recurse
+        let len = 100_000;
+        let arena: Arena<usize> = (0..len)
+            .map(|i| match i % 2 {
+                0 => (i, Some(i + 1).filter(|&next| next < len), None),
+                _ => (i, None, Some(i + 1).filter(|&next| next < len)),
+            })
+            .collect();
+        let tree = assemble(arena);
+
+        let text = to_parens(&tree);
+        assert!(from_parens::<usize>(&text)
+            .unwrap()
+            .pre_order()
+            .eq(tree.pre_order()));
+
+        let levels = to_level_order(&tree);
+        assert_eq!(levels.len(), 2 * len - 2);
+        assert!(from_level_order(levels)
+            .unwrap()
+            .pre_order()
+            .eq(tree.pre_order()));
+
+        let pre: Vec<usize> = tree.pre_order().copied().collect();
+        let ino: Vec<usize> = tree.in_order().copied().collect();
+        assert!(from_pre_in_order(&pre, &ino)
+            .unwrap()
+            .in_order()
+            .eq(ino.iter()));
+    }
+}